@@ -0,0 +1,204 @@
+// aggregation of the same pair prices across multiple providers
+
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_core::U256;
+use sp_runtime::RuntimeDebug;
+use sp_std::{convert::TryFrom, vec::Vec};
+use crate::types::PriceQuote;
+use crate::utils::breaches_tolerance;
+
+/// Method of aggregating the same pair prices across providers.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo)]
+pub enum AggregationMethod {
+	/// No aggregation, each provider's price remains a separate edge
+	None,
+	/// Median of the provider prices
+	Median,
+	/// Mean of the provider prices, after trimming given percent of the lowest and highest prices
+	TrimmedMean(u8),
+	/// Volume weighted average of the provider prices, falls back to mean if no volumes are quoted
+	Vwap,
+}
+
+/// Aggregated price of a pair, attributed to the provider with the closest price.
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct Aggregate<P> {
+	pub price: u128,
+	pub provider: P,
+	pub discarded: Vec<P>,
+}
+
+/// Aggregate the same pair quotes across providers.
+///
+/// Quotes deviating from the median by more than `max_deviation`, expressed in 1/1,000,000, are discarded as outliers,
+/// remaining quotes are aggregated as per the method. Returns `None` if no quotes agree with the median, or the aggregation overflows.
+pub fn aggregate_quotes<P: Clone + Ord>(method: AggregationMethod, max_deviation: u32, quotes: &[(P, PriceQuote<u128>)]) -> Option<Aggregate<P>> {
+	let consensus = median(&quotes.iter().map(|(_, q)| q.price).collect::<Vec<_>>())?;
+	if consensus == 0 {
+		return None
+	}
+	let (accepted, discarded): (Vec<_>, Vec<_>) = quotes.iter().partition(|(_, q)| !breaches_tolerance(consensus, q.price, max_deviation));
+	let accepted_prices = accepted.iter().map(|(_, q)| q.price).collect::<Vec<_>>();
+	let price = match method {
+		AggregationMethod::None | AggregationMethod::Median => median(&accepted_prices),
+		AggregationMethod::TrimmedMean(trim_percent) => trimmed_mean(&accepted_prices, trim_percent),
+		AggregationMethod::Vwap => vwap(&accepted.iter().map(|(_, q)| q.clone()).collect::<Vec<_>>()),
+	}?;
+	let (provider, _) = accepted.iter().min_by(|(p1, q1), (p2, q2)| distance(q1.price, price).cmp(&distance(q2.price, price)).then_with(|| p1.cmp(p2)))?;
+	Some(Aggregate { price, provider: provider.clone(), discarded: discarded.into_iter().map(|(p, _)| p.clone()).collect() })
+}
+
+/// Median, averaging the middle prices for even number of prices
+pub fn median(prices: &[u128]) -> Option<u128> {
+	let mut prices = prices.to_vec();
+	prices.sort_unstable();
+	let mid = prices.len() / 2;
+	match prices.len() {
+		0 => None,
+		len if len % 2 == 1 => Some(prices[mid]),
+		_ => Some(mean2(prices[mid - 1], prices[mid])),
+	}
+}
+
+/// Mean after trimming `trim_percent` of the lowest and highest prices, falls back to median if nothing is left after trimming
+pub fn trimmed_mean(prices: &[u128], trim_percent: u8) -> Option<u128> {
+	let mut prices = prices.to_vec();
+	prices.sort_unstable();
+	let trim = prices.len() * trim_percent.min(100) as usize / 100;
+	if prices.len() <= 2 * trim {
+		return median(&prices)
+	}
+	mean(&prices[trim..prices.len() - trim])
+}
+
+/// Volume weighted average price, ignoring quotes without volume. Falls back to mean if no volumes are quoted
+///
+/// Sums are widened to 256 bits, hence only fails if they, or the resulting price, overflow.
+pub fn vwap(quotes: &[PriceQuote<u128>]) -> Option<u128> {
+	let (weighted_sum, volume_sum) = quotes.iter()
+		.filter_map(|q| q.volume.filter(|v| *v > 0).map(|v| (q.price, v)))
+		.try_fold((U256::zero(), U256::zero()), |(weighted_sum, volume_sum), (price, volume)| Some((
+			weighted_sum.checked_add(U256::from(price) * U256::from(volume))?,
+			volume_sum.checked_add(U256::from(volume))?,
+		)))?;
+	if volume_sum.is_zero() {
+		mean(&quotes.iter().map(|q| q.price).collect::<Vec<_>>())
+	} else {
+		u128::try_from(weighted_sum / volume_sum).ok()
+	}
+}
+
+/// Confidence of the aggregated `price`, expressed in 1/1,000,000, as the product of:
+/// - agreement, ie. the share of `full_confidence_providers` agreeing on the price (the accepted `prices`), reduced by the share of outliers amongst all `quoted` providers
+/// - dispersion, ie. the maximum deviation of the accepted prices from the aggregated price, relative to `max_deviation`
+pub fn confidence(prices: &[u128], quoted: usize, price: u128, max_deviation: u32, full_confidence_providers: u32) -> u32 {
+	let agreeing = prices.len() as u128;
+	if agreeing == 0 || price == 0 {
+		return 0
+	}
+	let full_share = match full_confidence_providers {
+		0 => 1_000_000,
+		full => agreeing.min(full as u128) * 1_000_000 / full as u128,
+	};
+	let agreement = full_share * agreeing / (quoted as u128).max(agreeing);
+	let deviation = prices.iter().map(|p| distance(*p, price).saturating_mul(1_000_000) / price).max().unwrap_or_default();
+	let dispersion = match max_deviation {
+		0 => if deviation == 0 { 1_000_000 } else { 0 },
+		max_deviation => 1_000_000 - (deviation.saturating_mul(1_000_000) / max_deviation as u128).min(1_000_000),
+	};
+	(agreement * dispersion / 1_000_000) as u32
+}
+
+fn mean(prices: &[u128]) -> Option<u128> {
+	match prices.len() {
+		0 => None,
+		len => Some(prices.iter().fold(0_u128, |acc, p| acc.saturating_add(*p)) / len as u128),
+	}
+}
+
+fn distance(a: u128, b: u128) -> u128 {
+	if a > b { a - b } else { b - a }
+}
+
+fn mean2(a: u128, b: u128) -> u128 {
+	a / 2 + b / 2 + (a % 2 + b % 2) / 2
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn quote(price: u128) -> PriceQuote<u128> {
+        PriceQuote::of_price(price)
+    }
+
+    #[test]
+    fn test_median() {
+        assert_eq!(None,      median(&[]));
+        assert_eq!(Some(5),   median(&[5]));
+        assert_eq!(Some(5),   median(&[9, 1, 5]));
+        assert_eq!(Some(6),   median(&[9, 1, 5, 7]));
+        assert_eq!(Some(u128::MAX), median(&[u128::MAX, u128::MAX]));
+    }
+
+    #[test]
+    fn test_trimmed_mean() {
+        assert_eq!(None,      trimmed_mean(&[], 10));
+        assert_eq!(Some(5),   trimmed_mean(&[1, 5, 9], 0));
+        assert_eq!(Some(55),  trimmed_mean(&[1, 50, 55, 60, 1000], 20));
+        assert_eq!(Some(55),  trimmed_mean(&[1, 50, 55, 60, 1000], 100));  // median fallback
+    }
+
+    #[test]
+    fn test_vwap() {
+        assert_eq!(None,      vwap(&[]));
+        assert_eq!(Some(20),  vwap(&[quote(10), quote(30)]));
+        assert_eq!(Some(25),  vwap(&[PriceQuote { volume: Some(1), ..quote(10) }, PriceQuote { volume: Some(3), ..quote(30) }, quote(1000)]));
+        // products of prices and volumes beyond u128 aren't saturated
+        let (price, volume) = (10_u128.pow(30), 10_u128.pow(20));
+        assert_eq!(
+            Some(25 * 10_u128.pow(29)),
+            vwap(&[PriceQuote { volume: Some(volume), ..quote(price) }, PriceQuote { volume: Some(3 * volume), ..quote(3 * price) }]));
+        // sums beyond 256 bits fail, rather than saturate into a wrong price
+        assert_eq!(
+            None,
+            vwap(&[PriceQuote { volume: Some(u128::MAX), ..quote(u128::MAX) }, PriceQuote { volume: Some(u128::MAX), ..quote(u128::MAX) }]));
+    }
+
+    #[test]
+    fn test_aggregate_quotes() {
+        let quotes = vec![(1_u8, quote(100)), (2, quote(101)), (3, quote(102)), (4, quote(150)), (5, quote(99))];
+        // 150 deviates by ~47% from the median of 101
+        assert_eq!(
+            Some(Aggregate { price: 100, provider: 1, discarded: vec![4] }),
+            aggregate_quotes(AggregationMethod::Median, 50_000, &quotes));
+        assert_eq!(
+            Some(Aggregate { price: 100, provider: 1, discarded: vec![4] }),
+            aggregate_quotes(AggregationMethod::TrimmedMean(25), 50_000, &quotes));
+        assert_eq!(
+            Some(Aggregate { price: 110, provider: 3, discarded: vec![] }),
+            aggregate_quotes(AggregationMethod::Vwap, 500_000, &quotes));
+        // no quotes agree with the median
+        assert_eq!(None, aggregate_quotes(AggregationMethod::Median, 50_000, &[(1_u8, quote(100)), (2, quote(200))]));
+        assert_eq!(None, aggregate_quotes::<u8>(AggregationMethod::Median, 50_000, &[]));
+    }
+
+    #[test]
+    fn test_confidence() {
+        // 3 of 4 providers agreeing, within 2% of the 5% max deviation
+        assert_eq!(450_000, confidence(&[100, 102, 99], 4, 100, 50_000, 3));
+        // full confidence needs enough agreeing providers, at the same price
+        assert_eq!(1_000_000, confidence(&[100, 100, 100], 3, 100, 50_000, 3));
+        assert_eq!(1_000_000, confidence(&[100, 100, 100, 100], 4, 100, 50_000, 3));
+        assert_eq!(333_333, confidence(&[100], 1, 100, 50_000, 3));
+        assert_eq!(1_000_000, confidence(&[100], 1, 100, 50_000, 0));
+        // deviation at the max deviation
+        assert_eq!(0, confidence(&[100, 105], 2, 100, 50_000, 2));
+        assert_eq!(0, confidence(&[100, 101], 2, 100, 0, 2));
+        assert_eq!(1_000_000, confidence(&[100, 100], 2, 100, 0, 2));
+        // nothing to be confident about
+        assert_eq!(0, confidence(&[], 2, 100, 50_000, 2));
+        assert_eq!(0, confidence(&[100], 1, 0, 50_000, 2));
+    }
+}
@@ -1,73 +1,213 @@
 use super::*;
 
 #[allow(unused)]
-use crate::{Pallet as BestPath, PriceProviderId};
-use frame_benchmarking::benchmarks;
+use crate::Pallet as BestPath;
+use frame_benchmarking::{account, benchmarks, whitelisted_caller};
+use frame_support::{dispatch::UnfilteredDispatchable, unsigned::ValidateUnsigned};
 use frame_system::RawOrigin;
+use sp_runtime::{traits::One, transaction_validity::TransactionSource, RuntimeAppPublic};
+use traits::BenchmarkHelper;
 
-const MOCK_PROVIDER_ID: PriceProviderId = PriceProviderId::CRYPTOCOMPARE;
+/// Upper bound of monitored pair operations and provider tickers per call
+const MAX_OPERATIONS: u32 = 250;
+/// Upper bound of steps per best path
+const MAX_PATH_STEPS: u32 = 10;
+
+fn currency<T: Config>(prefix: &str, i: u32) -> T::Currency {
+	T::Currency::from_vecu8(format!("{}{}", prefix, i).into_bytes())
+}
+
+/// Path of `steps` hops from source to target via intermediate currencies, at the cost of 1 per step
+fn path<T: Config>(source: &T::Currency, target: &T::Currency, steps: u32) -> PricePath<T::Currency, T::Amount, T::Provider> {
+	let provider = T::BenchmarkHelper::provider();
+	let hops = (0..=steps)
+		.map(|i| match i {
+			0 => source.clone(),
+			i if i == steps => target.clone(),
+			i => currency::<T>("HOP", i),
+		})
+		.collect::<Vec<_>>();
+	PricePath {
+		total_cost: T::Amount::one(),
+		steps: hops.windows(2).map(|hop| PathStep{pair: Pair{source: hop[0].clone(), target: hop[1].clone()}, provider: provider.clone(), cost: T::Amount::one()}).collect(),
+	}
+}
+
+fn stored_path<T: Config>(path: PricePath<T::Currency, T::Amount, T::Provider>) -> StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber> {
+	StoredPricePath {
+		step_quotes: vec![StepQuote{bid: T::Amount::one(), ask: T::Amount::one()}; path.steps.len()],
+		confidence: MAX_CONFIDENCE,
+		path,
+		updated_at: frame_system::Pallet::<T>::block_number(),
+	}
+}
+
+/// Generate an offchain authority key in the keystore, and whitelist it
+fn authority<T: Config>() -> T::Public {
+	let public = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::RuntimeAppPublic::generate_pair(None);
+	let public: T::Public = <T::AuthorityId as AppCrypto<T::Public, T::Signature>>::GenericPublic::from(public).into();
+	WhitelistedOffchainAuthorities::<T>::insert(public.clone().into_account(), ());
+	public
+}
 
 benchmarks! {
-	add_price_pair_nonexisting {
-		let source = T::Currency::from_vecu8(b"BTC".to_vec());
-		let target = T::Currency::from_vecu8(b"ETH".to_vec());
-	}: add_price_pair(RawOrigin::Root, source.clone(), target.clone(), MOCK_PROVIDER_ID) 
+	submit_monitored_pairs {
+		let i in 1 .. MAX_OPERATIONS;
+		let provider = T::BenchmarkHelper::provider();
+		let mut operations = vec![];
+		for j in 0..i {
+			let provider_pair = ProviderPair{pair: Pair{source: currency::<T>("SRC", j), target: currency::<T>("TGT", j)}, provider: provider.clone()};
+			let operation = if j % 2 == 0 { Operation::Add } else { Operation::Del };
+			if operation == Operation::Del {
+				MonitoredPairs::<T>::insert(&provider_pair, PairOptions::default());
+			}
+			operations.push(ProviderPairOperation{provider_pair, operation, bidirectional: true});
+		}
+	}: _(RawOrigin::Root, operations)
 	verify {
-		assert!(MonitoredPairs::<T>::contains_key(ProviderPair{ pair: Pair{ source: source.clone(), target: target.clone() }, provider: MOCK_PROVIDER_ID}));
+		assert_eq!((i + 1) / 2, MonitoredPairs::<T>::iter_keys().count() as u32);
 	}
 
-	add_price_pair_existing {
-		let source = T::Currency::from_vecu8(b"ACA".to_vec());
-		let target = T::Currency::from_vecu8(b"KAR".to_vec());
-		MonitoredPairs::<T>::insert(ProviderPair{ pair: Pair{ source: source.clone(), target: target.clone() }, provider: MOCK_PROVIDER_ID }, ());
-	}: add_price_pair(RawOrigin::Root, source.clone(), target.clone(), MOCK_PROVIDER_ID)
+	ocw_submit_best_paths_changes {
+		let c in 1 .. T::MaxChangesPerTx::get();
+		let s in 1 .. T::MaxChangesPerTx::get() * MAX_PATH_STEPS;
+		frame_system::Pallet::<T>::set_block_number(One::one());
+		let public = authority::<T>();
+		let mut changes = vec![];
+		let mut confidences = vec![];
+		for i in 0..c {
+			let (source, target) = (currency::<T>("SRC", i), currency::<T>("TGT", i));
+			// steps spread evenly across the changes, totalling `s`, ie. paths without steps while `s` is below `c`
+			let path = path::<T>(&source, &target, s / c + u32::from(i < s % c));
+			// existing paths of the same cost, for the changes to be stored rather than held as suspicious
+			BestPaths::<T>::insert(&source, &target, stored_path::<T>(path.clone()));
+			confidences.push((source.clone(), target.clone(), MAX_CONFIDENCE));
+			changes.push((source, target, Some(path)));
+		}
+		let step_quotes = changes.iter()
+			.flat_map(|(_, _, path)| path.iter().flat_map(|path| path.steps.iter()))
+			.map(|step| (ProviderPair{pair: step.pair.clone(), provider: step.provider.clone()}, StepQuote{bid: step.cost, ask: step.cost}))
+			.collect::<Vec<_>>();
+		let nonce = UnsignedTxNonce::<T>::get();
+		let payload = BestPathChangesPayload {
+			changes,
+			step_quotes,
+			confidences,
+			provider_outages: vec![],
+			nonce,
+			block_number: frame_system::Pallet::<T>::block_number(),
+			public,
+		};
+		let signature = <BestPathChangesPayload<T::Public, T::BlockNumber, T::Currency, T::Amount, T::Provider> as SignedPayload<T>>::sign::<T::AuthorityId>(&payload)
+			.ok_or("Unable to sign payload")?;
+		let call = Call::<T>::ocw_submit_best_paths_changes { best_path_change_payload: payload, signature };
+	}: {
+		BestPath::<T>::validate_unsigned(TransactionSource::External, &call).map_err(<&str>::from)?;
+		call.dispatch_bypass_filter(RawOrigin::None.into())?;
+	}
 	verify {
-		assert!(MonitoredPairs::<T>::contains_key(ProviderPair{ pair: Pair{ source: source.clone(), target: target.clone() }, provider: MOCK_PROVIDER_ID}));
+		assert_eq!(nonce + 1, UnsignedTxNonce::<T>::get());
 	}
 
-	delete_price_pair {
-		let source = T::Currency::from_vecu8(b"ACA".to_vec());
-		let target = T::Currency::from_vecu8(b"KAR".to_vec());
-		MonitoredPairs::<T>::insert(ProviderPair{ pair: Pair{ source: source.clone(), target: target.clone() }, provider: MOCK_PROVIDER_ID }, ());
-	}: _(RawOrigin::Root, source.clone(), target.clone(), MOCK_PROVIDER_ID)
+	add_whitelisted_offchain_authority {
+		let offchain_authority: T::AccountId = account("authority", 0, 0);
+	}: _(RawOrigin::Root, offchain_authority.clone())
 	verify {
-		assert!(! MonitoredPairs::<T>::contains_key(ProviderPair{ pair: Pair{ source: source.clone(), target: target.clone() }, provider: MOCK_PROVIDER_ID}));
+		assert!(WhitelistedOffchainAuthorities::<T>::contains_key(&offchain_authority));
 	}
 
-	submit_price_pairs {
-		let i in 0 .. 250;
-		let mut pairs = vec![];
+	submit_currency_metadata {
+		let currency = currency::<T>("CUR", 0);
+		let metadata = CurrencyMeta{symbol: b"BTC".to_vec(), decimals: 8, display_name: b"Bitcoin".to_vec()};
+	}: _(RawOrigin::Root, currency.clone(), Some(metadata.clone()))
+	verify {
+		assert_eq!(Some(metadata), CurrencyMetadata::<T>::get(&currency));
+	}
+
+	submit_provider_tickers {
+		let i in 1 .. MAX_OPERATIONS;
+		let provider = T::BenchmarkHelper::provider();
+		let mut tickers = vec![];
 		for j in 0..i {
-			let k = j as u8;
-			let op = if k % 2 == 0 { Operation::Add } else { Operation::Del };
-			let source = T::Currency::from_vecu8(vec![k % 255_u8,     (k+1) % 255_u8, (k+2) % 255_u8]);
-			let target = T::Currency::from_vecu8(vec![(k+1) % 255_u8, (k+2) % 255_u8, (k+3) % 255_u8]);
-			pairs.push((
-				source.clone(),
-				target.clone(),
-				MOCK_PROVIDER_ID,
-				op.clone(),
-			));
-			if op == Operation::Del {
-				MonitoredPairs::<T>::insert(ProviderPair{ pair: Pair{ source, target }, provider: MOCK_PROVIDER_ID }, ());
-			}
+			// replacing existing tickers
+			let currency = currency::<T>("CUR", j);
+			let old_ticker = format!("OLD{}", j).into_bytes();
+			ProviderTickers::<T>::insert(&provider, &currency, &old_ticker);
+			ProviderTickerCurrencies::<T>::insert(&provider, &old_ticker, &currency);
+			tickers.push((provider.clone(), currency, Some(format!("NEW{}", j).into_bytes())));
 		}
-	}: submit_price_pairs(RawOrigin::Root, pairs)
+	}: _(RawOrigin::Root, tickers)
+	verify {
+		assert_eq!(b"NEW0".to_vec(), BestPath::<T>::provider_ticker(&provider, &currency::<T>("CUR", 0)));
+	}
 
-	ocw_submit_best_paths_changes {
-		// let i in 0 .. 250;
-		// let mut changes = vec![];
-		// let payload = BestPathChangesPayload{ changes: changes, nonce: 1, public: public } {
-		// 	changes: Vec<(C, C, Option<PricePath<C, A>>)>,
-		// 	nonce: u64,
-		// 	public: Public,
-		// }
-		// let signature = ();
-		// FIXME... complete!
-	}: add_price_pair(RawOrigin::Root, T::Currency::from_vecu8(b"ACA".to_vec()), T::Currency::from_vecu8(b"KAR".to_vec()), MOCK_PROVIDER_ID)
-
-	add_offchain_authority {
-	}: add_price_pair(RawOrigin::Root, T::Currency::from_vecu8(b"ACA".to_vec()), T::Currency::from_vecu8(b"KAR".to_vec()), MOCK_PROVIDER_ID)
-
-	impl_benchmark_test_suite!(BestPath, crate::mock::new_test_ext(), crate::mock::Test);
-}
\ No newline at end of file
+	submit_pair_tolerances {
+		let i in 1 .. MAX_OPERATIONS;
+		let tolerances = (0..i).map(|j| (currency::<T>("SRC", j), currency::<T>("TGT", j), Some(100))).collect::<Vec<_>>();
+	}: _(RawOrigin::Root, tolerances)
+	verify {
+		assert_eq!(i, PairTolerances::<T>::iter_keys().count() as u32);
+	}
+
+	submit_currency_groups {
+		let i in 1 .. MAX_OPERATIONS;
+		let groups = (0..i).map(|j| (currency::<T>("CUR", j), Some(b"stablecoins".to_vec()))).collect::<Vec<_>>();
+	}: _(RawOrigin::Root, groups)
+	verify {
+		assert_eq!(i, CurrencyGroups::<T>::iter_keys().count() as u32);
+	}
+
+	submit_group_tolerances {
+		let i in 1 .. MAX_OPERATIONS;
+		let tolerances = (0..i).map(|j| (format!("GROUP{}", j).into_bytes(), Some(100))).collect::<Vec<_>>();
+	}: _(RawOrigin::Root, tolerances)
+	verify {
+		assert_eq!(i, GroupTolerances::<T>::iter_keys().count() as u32);
+	}
+
+	approve_suspicious_change {
+		let (source, target) = (currency::<T>("SRC", 0), currency::<T>("TGT", 0));
+		let path = path::<T>(&source, &target, MAX_PATH_STEPS);
+		BestPaths::<T>::insert(&source, &target, stored_path::<T>(path.clone()));
+		PendingSuspiciousChanges::<T>::insert(&source, &target, stored_path::<T>(path));
+	}: _(RawOrigin::Root, source.clone(), target.clone())
+	verify {
+		assert!(!PendingSuspiciousChanges::<T>::contains_key(&source, &target));
+		assert!(BestPaths::<T>::contains_key(&source, &target));
+	}
+
+	reject_suspicious_change {
+		let (source, target) = (currency::<T>("SRC", 0), currency::<T>("TGT", 0));
+		PendingSuspiciousChanges::<T>::insert(&source, &target, stored_path::<T>(path::<T>(&source, &target, MAX_PATH_STEPS)));
+	}: _(RawOrigin::Root, source.clone(), target.clone())
+	verify {
+		assert!(!PendingSuspiciousChanges::<T>::contains_key(&source, &target));
+	}
+
+	pause {
+		let provider = T::BenchmarkHelper::provider();
+	}: _(RawOrigin::Root, Some(provider.clone()))
+	verify {
+		assert!(PausedProviders::<T>::contains_key(&provider));
+	}
+
+	unpause {
+		let provider = T::BenchmarkHelper::provider();
+		PausedProviders::<T>::insert(&provider, ());
+	}: _(RawOrigin::Root, Some(provider.clone()))
+	verify {
+		assert!(!PausedProviders::<T>::contains_key(&provider));
+	}
+
+	execute_best_path {
+		let h in 1 .. T::MaxExecutionHops::get();
+		let who: T::AccountId = whitelisted_caller();
+		let (source, target) = (currency::<T>("SRC", 0), currency::<T>("TGT", 0));
+		let path = path::<T>(&source, &target, h);
+		let amount_in = T::Amount::one();
+		T::BenchmarkHelper::setup_execution(&who, &path, amount_in);
+		BestPaths::<T>::insert(&source, &target, stored_path::<T>(path));
+	}: _(RawOrigin::Signed(who), source, target, amount_in, Zero::zero())
+
+	impl_benchmark_test_suite!(BestPath, crate::mock::new_test_ext_with_keystore().0, crate::mock::Test);
+}
@@ -2,7 +2,7 @@
 #![allow(clippy::type_complexity)]
 
 use core::fmt::Debug;
-use codec::{Decode, Encode, FullCodec};
+use codec::{Decode, DecodeAll, Encode, FullCodec};
 use frame_support::{pallet_prelude::*, traits::Get, traits::tokens::Balance, transactional};
 use frame_system::{
 	self,
@@ -11,12 +11,13 @@ use frame_system::{
 use sp_core::crypto::KeyTypeId;
 use sp_runtime::{
 	offchain::{http, Duration, storage::StorageValueRef, storage_lock::{StorageLock, Time}},
-	traits::IdentifyAccount,
+	traits::{CheckedSub, IdentifyAccount, MaybeSerializeDeserialize, One, Saturating, Zero},
+	SaturatedConversion,
 	transaction_validity::{InvalidTransaction, TransactionValidity, ValidTransaction},
 	RuntimeDebug,
 };
 use sp_std::{
-	collections::btree_set::BTreeSet,
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
 	convert::TryInto,
 	iter::Iterator,
 	vec::Vec,
@@ -29,11 +30,14 @@ use types::*;
 mod utils;
 use utils::*;
 pub mod traits;
-use traits::{BestPath as BestPathTrait};
+use traits::{BestPath as BestPathTrait, BestPathHistory, DexPriceSource, OnBestPathChanged, PathExecutor};
 pub use best_path;
 use best_path::{BestPathCalculator, prelude::*};
 pub mod heap;
 pub mod price_provider;
+pub mod aggregation;
+pub mod migrations;
+use aggregation::{aggregate_quotes, confidence, Aggregate, AggregationMethod};
 use scale_info::{prelude::{string::String, format}, TypeInfo};
 
 #[cfg(test)]
@@ -62,22 +66,86 @@ pub const OCW_WORKER_LOCK: &[u8] = b"best_path::ocw_lock";
 /// Key for the next offchain trigger.
 pub const NEXT_OFFCHAIN_TRIGGER_BLOCK: &[u8] = b"best_path::next_offchain_trigger_block";
 
+/// Key prefix for the per provider health, suffixed with the encoded provider.
+pub const PROVIDER_HEALTH_PREFIX: &[u8] = b"best_path::provider_health::";
+
+/// Key for the counters of price fetch failures, by failure kind.
+pub const FETCH_FAILURE_STATS: &[u8] = b"best_path::fetch_failure_stats";
+
+/// Key for the latest price fetches, by monitored pair. Orders the fetches of subsequent triggers, least recently attempted first.
+pub const PRICE_CACHE: &[u8] = b"best_path::price_cache";
+/// Prefix of offchain index keys of path change records, followed by the encoded (block number, extrinsic index, source, target).
+pub const PATH_CHANGE_INDEX_PREFIX: &[u8] = b"best_path::path_change::";
+
+/// Full confidence of a best path, confidences are expressed in 1/1,000,000.
+pub const MAX_CONFIDENCE: u32 = 1_000_000;
+
 /// Available Price Providers
 #[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug, TypeInfo, Ord, PartialOrd)]
+#[cfg_attr(feature = "std", derive(frame_support::Serialize, frame_support::Deserialize))]
+#[cfg_attr(feature = "std", serde(crate = "frame_support::serde"))]
 #[allow(clippy::upper_case_acronyms)]
 pub enum PriceProviderId {
-    CRYPTOCOMPARE
+    CRYPTOCOMPARE,
+    KRAKEN,
+    DEX,
+}
+
+/// Price query of a single pair, with currencies resolved to provider tickers
+#[derive(Clone, Eq, PartialEq, RuntimeDebug)]
+pub struct PriceQuery<'a> {
+	/// Source currency ticker, as known to the provider
+	pub source: &'a [u8],
+	/// Target currency ticker, as known to the provider
+	pub target: &'a [u8],
+	/// Fixed point scale of the resulting price
+	pub scale: u32,
 }
 
 /// Implementor of price fetching mechanism, per provider
 pub trait PriceProviderHub<A: Amount, P: Eq> {
-	/// For a given provider, source & target currency, fetch the pair price
-	fn get_price<C: AsRef<[u8]>>(provider: &P, source: C, target: C) -> Result<A, PriceProviderErr>;
+	/// For a given provider and query, fetch the quote of a whole source unit in whole target units, scaled by `10^query.scale`
+	fn get_price(provider: &P, query: &PriceQuery) -> Result<PriceQuote<A>, PriceProviderErr>;
 }
 
-#[derive(Debug)]
+/// Reasons of price fetching failures
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub enum PriceProviderErr {
+	/// Transport failure, eg. IO error or deadline reached
 	TransportErr(http::Error),
+	/// Unexpected, non 200 HTTP status code
+	HttpStatusErr(u16),
+	/// Provider rate limited the request, ie. HTTP status code 429
+	RateLimitedErr,
+	/// Response not parsable or missing the price
+	ParseErr,
+	/// Currency pair not supported by the provider
+	UnsupportedPairErr,
+	/// Zero or negative price
+	InvalidPriceErr,
+}
+
+impl PriceProviderErr {
+	/// Is the failure likely to be resolved by retrying?
+	pub fn is_transient(&self) -> bool {
+		match self {
+			PriceProviderErr::TransportErr(_) | PriceProviderErr::RateLimitedErr => true,
+			PriceProviderErr::HttpStatusErr(code) => *code >= 500,
+			_ => false,
+		}
+	}
+
+	/// Failure kind, as recorded in the fetch failure metrics
+	pub fn kind(&self) -> &'static [u8] {
+		match self {
+			PriceProviderErr::TransportErr(_) => b"transport",
+			PriceProviderErr::HttpStatusErr(_) => b"http_status",
+			PriceProviderErr::RateLimitedErr => b"rate_limited",
+			PriceProviderErr::ParseErr => b"parse",
+			PriceProviderErr::UnsupportedPairErr => b"unsupported_pair",
+			PriceProviderErr::InvalidPriceErr => b"invalid_price",
+		}
+	}
 }
 
 impl From<http::Error> for PriceProviderErr {
@@ -92,11 +160,19 @@ impl From<http::Error> for PriceProviderErr {
 #[derive(Encode, Decode, Clone, PartialEq, Eq, TypeInfo, RuntimeDebug)]
 pub struct BestPathChangesPayload<Public, BlockNumber, C: Currency, A: Amount, P: Provider> {
 	changes: Vec<(C, C, Option<PricePath<C, A, P>>)>,
+	/// Bid/ask quotes of the steps of changed paths
+	step_quotes: Vec<(ProviderPair<C, P>, StepQuote<A>)>,
+	/// Confidence of changed paths, by source & target currencies, expressed in 1/1,000,000
+	confidences: Vec<(C, C, u32)>,
+	provider_outages: Vec<P>,
 	nonce: u64,
 	block_number: BlockNumber,
 	public: Public,
 }
 
+/// Best path changes of a single payload, along with the step quotes and confidences of the changed paths
+pub type ChangesChunk<C, A, P> = (Vec<(C, C, Option<PricePath<C, A, P>>)>, Vec<(ProviderPair<C, P>, StepQuote<A>)>, Vec<(C, C, u32)>);
+
 impl<T: SigningTypes, C: Currency + Encode, A: Amount + Encode, P: Provider + Encode> SignedPayload<T> for BestPathChangesPayload<T::Public, T::BlockNumber, C, A, P> {
 	fn public(&self) -> T::Public {
 		self.public.clone()
@@ -136,28 +212,87 @@ pub mod pallet {
 	use super::*;
 	use frame_system::pallet_prelude::*;
 
+	/// Current storage version, bumped with every migration of stored encodings
+	pub(crate) const STORAGE_VERSION: StorageVersion = StorageVersion::new(1);
+
 	/// DoubleMap of trading path by source & target currencies
 	#[pallet::storage]
-	pub(super) type BestPaths<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, PricePath<T::Currency, T::Amount, T::Provider> /* best path */>;
+	pub(super) type BestPaths<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber> /* best path, updated at */>;
 
 	/// Map to keep track of source & target currencies we wish to monitor
 	#[pallet::storage]
-	pub(super) type MonitoredPairs<T: Config> = StorageMap<_, Blake2_128Concat, ProviderPair<T::Currency, T::Provider>, (), OptionQuery>;  // membership in the map indicates price is to be fetched, Some(()) - existence of the latest price
+	pub(super) type MonitoredPairs<T: Config> = StorageMap<_, Blake2_128Concat, ProviderPair<T::Currency, T::Provider>, PairOptions, OptionQuery>;  // membership in the map indicates price is to be fetched, with the pair options
 
 	/// Map storing whitelisted accounts that are whitelisted to sign the payload of unsigned transactions.
 	#[pallet::storage]
 	pub(super) type WhitelistedOffchainAuthorities<T: Config> = StorageMap<_, Blake2_128Concat, T::AccountId, ()>;
 
+	/// Map of currency metadata, such as decimals and display name
+	#[pallet::storage]
+	pub(super) type CurrencyMetadata<T: Config> = StorageMap<_, Blake2_128Concat, T::Currency, CurrencyMeta>;
+
+	/// DoubleMap of currency tickers by provider, for providers that use different tickers, eg. XBT vs BTC
+	#[pallet::storage]
+	pub(super) type ProviderTickers<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Provider, Blake2_128Concat, T::Currency, Vec<u8> /* ticker */>;
+
+	/// Reverse of `ProviderTickers`, DoubleMap of currencies by provider ticker, keeping tickers unique per provider
+	#[pallet::storage]
+	pub(super) type ProviderTickerCurrencies<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Provider, Blake2_128Concat, Vec<u8> /* ticker */, T::Currency>;
+
+	/// DoubleMap of best path changes breaching `MaxPriceChange`/`MaxWindowPriceChange`, held until confirmed or approved, by source & target currencies
+	#[pallet::storage]
+	pub(super) type PendingSuspiciousChanges<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber> /* held path, updated at the block held at */>;
+
+	/// Map of source & target currencies whose `PendingSuspiciousChanges` expire at the block, pruned in `on_initialize`
+	#[pallet::storage]
+	pub(super) type SuspiciousChangeExpiry<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(T::Currency, T::Currency)>, ValueQuery>;
+
+	/// DoubleMap of the start block and cost of the current `PriceChangeWindow`, by source & target currencies
+	#[pallet::storage]
+	pub(super) type PriceWindowAnchors<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, (T::BlockNumber, T::Amount) /* window start, cost at window start */>;
+
+	/// DoubleMap of the latest `PathHistoryDepth` best path snapshots by source & target currencies, ordered from the oldest
+	#[pallet::storage]
+	pub(super) type PathHistory<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, Vec<StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber>>, ValueQuery>;
+
+	/// Map of source & target currencies whose `PathHistory` snapshots expire at the block, pruned in `on_initialize`
+	#[pallet::storage]
+	pub(super) type PathHistoryExpiry<T: Config> = StorageMap<_, Twox64Concat, T::BlockNumber, Vec<(T::Currency, T::Currency)>, ValueQuery>;
+
+	/// DoubleMap of the latest `MaxTwapObservations` cumulative price observations by source & target currencies, ordered from the oldest
+	#[pallet::storage]
+	pub(super) type TwapObservations<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, Vec<TwapObservation<T::BlockNumber>>, ValueQuery>;
+
+	/// Emergency freeze switch, when set, best path updates are rejected and OCW skips work
+	#[pallet::storage]
+	pub(super) type Paused<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// Map of paused providers, best path updates via these providers are rejected and their prices aren't fetched
+	#[pallet::storage]
+	pub(super) type PausedProviders<T: Config> = StorageMap<_, Blake2_128Concat, T::Provider, ()>;
+
 	/// Nonce used for replay protection of unsigned transactions
 	#[pallet::storage]
 	pub(super) type UnsignedTxNonce<T: Config> = StorageValue<_, u64, ValueQuery>;
 
+	/// DoubleMap of price change tolerance overrides by source & target currencies, expressed in 1/1,000,000, taking precedence over the group tolerances
+	#[pallet::storage]
+	pub(super) type PairTolerances<T: Config> = StorageDoubleMap<_, Blake2_128Concat, T::Currency /* source currency */, Blake2_128Concat, T::Currency /* target currency */, u32 /* tolerance */>;
+
+	/// Map of currency groups, eg. stablecoins, by currency
+	#[pallet::storage]
+	pub(super) type CurrencyGroups<T: Config> = StorageMap<_, Blake2_128Concat, T::Currency, Vec<u8> /* group */>;
+
+	/// Map of price change tolerance overrides by currency group, expressed in 1/1,000,000
+	#[pallet::storage]
+	pub(super) type GroupTolerances<T: Config> = StorageMap<_, Blake2_128Concat, Vec<u8> /* group */, u32 /* tolerance */>;
+
 	#[pallet::event]
 	#[pallet::generate_deposit(pub(super) fn deposit_event)]
 	pub enum Event<T: Config> {
-		/// Submission of best prices onchain
-		/// \[{source_currency, target_currency, new_cost, operation}\]
-		BestPricesSubmitted(Vec<(T::Currency, T::Currency, T::Amount, Operation)>),
+		/// Submission of best prices onchain, by the offchain authority.
+		/// \[authority, {source_currency, target_currency, new_path, operation}\]
+		BestPricesSubmitted(T::AccountId, Vec<(T::Currency, T::Currency, Option<PricePath<T::Currency, T::Amount, T::Provider>>, Operation)>),
 
 		/// Addition of a monitored currency/provider pair.
 		/// \[source_currency, target_currency, provider, operation\]
@@ -166,6 +301,54 @@ pub mod pallet {
 		/// Addition of offchain authority account.
 		/// \[account_id\]
 		WhitelistedOffchainAuthorityAdded(T::AccountId),
+
+		/// Providers deemed unhealthy by the OCW, skipped for the `ProviderCooldown` period.
+		/// \[providers\]
+		ProviderOutagesReported(Vec<T::Provider>),
+
+		/// Addition/deletion of currency metadata.
+		/// \[currency, operation\]
+		CurrencyMetadataSubmitted(T::Currency, Operation),
+
+		/// Addition/deletion of provider tickers.
+		/// \[{provider, currency, ticker}\]
+		ProviderTickersSubmitted(Vec<(T::Provider, T::Currency, Option<Vec<u8>>)>),
+
+		/// Addition/deletion of price change tolerance overrides of currency pairs.
+		/// \[{source_currency, target_currency, tolerance}\]
+		PairTolerancesSubmitted(Vec<(T::Currency, T::Currency, Option<u32>)>),
+
+		/// Addition/deletion of currency group memberships.
+		/// \[{currency, group}\]
+		CurrencyGroupsSubmitted(Vec<(T::Currency, Option<Vec<u8>>)>),
+
+		/// Addition/deletion of price change tolerance overrides of currency groups.
+		/// \[{group, tolerance}\]
+		GroupTolerancesSubmitted(Vec<(Vec<u8>, Option<u32>)>),
+
+		/// Best path change breaching `MaxPriceChange`/`MaxWindowPriceChange`, held in `PendingSuspiciousChanges`.
+		/// \[source_currency, target_currency, old_cost, new_cost\]
+		SuspiciousChangeHeld(T::Currency, T::Currency, T::Amount, T::Amount),
+
+		/// Held best path change confirmed by a subsequent OCW submission or approved by the admin, and written onchain.
+		/// \[source_currency, target_currency, new_cost\]
+		SuspiciousChangeReleased(T::Currency, T::Currency, T::Amount),
+
+		/// Held best path change rejected by the admin.
+		/// \[source_currency, target_currency\]
+		SuspiciousChangeRejected(T::Currency, T::Currency),
+
+		/// Pausing of best path updates, globally if no provider is specified.
+		/// \[provider\]
+		Paused(Option<T::Provider>),
+
+		/// Unpausing of best path updates, globally if no provider is specified.
+		/// \[provider\]
+		Unpaused(Option<T::Provider>),
+
+		/// Execution of a best path trade.
+		/// \[account_id, source_currency, target_currency, amount_in, amount_out\]
+		BestPathExecuted(T::AccountId, T::Currency, T::Currency, T::Amount, T::Amount),
 	}
 
 	#[pallet::error]
@@ -174,6 +357,20 @@ pub mod pallet {
 		PricePairNotFoundError,
 		/// Indicates stale unsigned transaction, possibly due to replay attack
 		StaleUnsignedTxError,
+		/// Indicates provider ticker already mapped to another currency
+		DuplicateProviderTickerError,
+		/// Indicates currency listed more than once for the same provider
+		DuplicateProviderCurrencyError,
+		/// Indicates no held best path change for the currency pair
+		SuspiciousChangeNotFoundError,
+		/// Indicates best path updates are paused
+		PalletPausedError,
+		/// Indicates best path without steps, or with more than `MaxExecutionHops` steps
+		PathNotExecutableError,
+		/// Indicates best path execution yielding less than the requested minimum
+		SlippageExceededError,
+		/// Indicates OCW payload with more than `MaxChangesPerTx` best path changes
+		TooManyChangesError,
 	}
 	
 	/// This pallet's configuration trait
@@ -189,10 +386,10 @@ pub mod pallet {
 		type Call: From<Call<Self>>;
 
 		/// Currency type
-		type Currency: Currency + Conversions + AsRef<[u8]> + FullCodec + TypeInfo + Debug;
+		type Currency: Currency + Conversions + AsRef<[u8]> + FullCodec + TypeInfo + Debug + MaybeSerializeDeserialize;
 
-		/// Currency type
-		type Provider: Provider + FullCodec + TypeInfo + Debug;
+		/// Provider type
+		type Provider: Provider + FullCodec + TypeInfo + Debug + MaybeSerializeDeserialize;
 
 		/// Type indicating amounts: price, cost, balance
 		type Amount: Balance;
@@ -203,6 +400,19 @@ pub mod pallet {
 		/// Dynamic implementation of the price oracle, per provider
 		type PriceProviderHub: PriceProviderHub<Self::Amount, Self::Provider>;
 
+		/// Handler of best path additions, changes and removals, for dependent pallets
+		type OnBestPathChanged: OnBestPathChanged<Self::Currency, Self::Amount, Self::Provider>;
+
+		/// Source of onchain pool prices, for pairs monitored under `DexProvider`
+		type DexPriceSource: DexPriceSource<Self::Currency, Self::Amount>;
+
+		/// Executors of best path steps, per provider, eg. DEX pallet adapters
+		type Executors: PathExecutor<Self::AccountId, Self::Currency, Self::Amount, Self::Provider>;
+
+		/// Runtime specific setup of benchmarks
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: traits::BenchmarkHelper<Self::AccountId, Self::Currency, Self::Amount, Self::Provider>;
+
 		/// Benchmarking weight type
 		type WeightInfo: WeightInfo;
 
@@ -220,22 +430,223 @@ pub mod pallet {
 		#[pallet::constant]
 		type UnsignedPriority: Get<TransactionPriority>;
 
-		/// Tolerance of price change in best paths, expressed in 1/1,000,000, filters out insignificant price changes
+		/// Tolerance of price change in best paths, expressed in 1/1,000,000, filters out insignificant price changes.
+		/// Default for pairs without an override in `PairTolerances` or `GroupTolerances`
 		#[pallet::constant]
 		type PriceChangeTolerance: Get<u32>;
+
+		/// Number of blocks since the latest update of a best path, after which its update is submitted regardless of the tolerance, 0 disables
+		#[pallet::constant]
+		type MaxPathAge: Get<Self::BlockNumber>;
+
+		/// Fixed point scale of prices and path costs, ie. cost of 1 is represented as `10^PriceScale`
+		#[pallet::constant]
+		type PriceScale: Get<u32>;
+
+		/// Method of aggregating the same pair prices across providers, prior to best path calculation
+		#[pallet::constant]
+		type PriceAggregation: Get<AggregationMethod>;
+
+		/// Maximum deviation of a provider price from the cross provider median, expressed in 1/1,000,000, beyond which the price is discarded as an outlier
+		#[pallet::constant]
+		type MaxProviderDeviation: Get<u32>;
+
+		/// Number of retries of a failed price fetch, within a single OCW run
+		#[pallet::constant]
+		type PriceFetchRetries: Get<u32>;
+
+		/// Delay before the first price fetch retry, in millis, doubled with every subsequent retry
+		#[pallet::constant]
+		type PriceFetchBackoff: Get<u64>;
+
+		/// Number of consecutive failed price fetches after which the provider is deemed unhealthy
+		#[pallet::constant]
+		type ProviderFailureThreshold: Get<u32>;
+
+		/// Number of blocks for which an unhealthy provider is skipped by the OCW
+		#[pallet::constant]
+		type ProviderCooldown: Get<Self::BlockNumber>;
+
+		/// Should provider outages be reported onchain, via the `ProviderOutagesReported` event
+		#[pallet::constant]
+		type ReportProviderOutages: Get<bool>;
+
+		/// Maximum price change of a best path in a single update, expressed in 1/1,000,000, beyond which the change is held as suspicious
+		#[pallet::constant]
+		type MaxPriceChange: Get<u32>;
+
+		/// Maximum price change of a best path within a `PriceChangeWindow`, expressed in 1/1,000,000, beyond which the change is held as suspicious
+		#[pallet::constant]
+		type MaxWindowPriceChange: Get<u32>;
+
+		/// Number of blocks over which `MaxWindowPriceChange` is enforced
+		#[pallet::constant]
+		type PriceChangeWindow: Get<Self::BlockNumber>;
+
+		/// Number of blocks within which a held suspicious change can be confirmed by a subsequent OCW round, or approved, after which it expires.
+		/// Should cover the `OffchainTriggerDelay` of the next round
+		#[pallet::constant]
+		type SuspiciousChangeTimeout: Get<Self::BlockNumber>;
+
+		/// Should `BestPath::get_price_path` hide paths while paused, globally or via a paused provider
+		#[pallet::constant]
+		type PausedHidesPaths: Get<bool>;
+
+		/// Maximum number of steps of an executed best path
+		#[pallet::constant]
+		type MaxExecutionHops: Get<u32>;
+
+		/// Number of best path snapshots kept in `PathHistory` per pair, 0 disables the history
+		#[pallet::constant]
+		type PathHistoryDepth: Get<u32>;
+
+		/// Number of blocks after which `PathHistory` snapshots are pruned, 0 keeps them until displaced by newer ones
+		#[pallet::constant]
+		type PathHistoryRetention: Get<Self::BlockNumber>;
+
+		/// Number of cumulative price observations kept per pair, bounding the TWAP window to as many best path updates
+		#[pallet::constant]
+		type MaxTwapObservations: Get<u32>;
+
+		/// Provider whose monitored pairs are priced from onchain pools via `DexPriceSource`, rather than fetched by the OCW, if any
+		#[pallet::constant]
+		type DexProvider: Get<Option<Self::Provider>>;
+
+		/// Whether inverse edges are derived for all monitored pairs, regardless of their `bidirectional` option
+		#[pallet::constant]
+		type DeriveInversePairs: Get<bool>;
+
+		/// Spread haircut of derived inverse edge prices, expressed in 1/1,000,000
+		#[pallet::constant]
+		type InversePairHaircut: Get<u32>;
+
+		/// Spread between bid and ask assumed around the price of providers not quoting them, expressed in 1/1,000,000
+		#[pallet::constant]
+		type DefaultSpread: Get<u32>;
+
+		/// Number of providers agreeing on a price required for its full confidence
+		#[pallet::constant]
+		type FullConfidenceProviders: Get<u32>;
+
+		/// Number of blocks over which the confidence of a best path decays to 0 since its latest update, 0 disables the decay
+		#[pallet::constant]
+		type ConfidenceDecayPeriod: Get<Self::BlockNumber>;
+
+		/// Offchain time the OCW may spend fetching prices per trigger, in millis, 0 for no limit.
+		/// Pairs not fetched within the budget are priced from their latest fetch, and fetched first on the next trigger.
+		/// Pairs of the `DexProvider` are read from the onchain pools regardless
+		#[pallet::constant]
+		type OcwTimeBudget: Get<u64>;
+
+		/// Number of blocks a cached quote may price a pair left over by the `OcwTimeBudget`, older quotes are discarded
+		#[pallet::constant]
+		type MaxCachedQuoteAge: Get<Self::BlockNumber>;
+
+		/// Maximum number of best path changes per OCW submission, larger change sets are split into nonce sequenced submissions.
+		/// Must be positive, as asserted by the integrity test
+		#[pallet::constant]
+		type MaxChangesPerTx: Get<u32>;
+	}
+
+	/// Initial state of the pallet, for dev/test networks that would otherwise require manual setup via admin extrinsics
+	#[pallet::genesis_config]
+	pub struct GenesisConfig<T: Config> {
+		/// Monitored pairs, as (source currency, target currency, provider)
+		pub monitored_pairs: Vec<(T::Currency, T::Currency, T::Provider)>,
+		/// Whitelisted offchain authority accounts
+		pub authorities: Vec<T::AccountId>,
+		/// Currency metadata, such as decimals and display name
+		pub currency_metadata: Vec<(T::Currency, CurrencyMeta)>,
+		/// Provider specific tickers, as (provider, currency, ticker)
+		pub provider_tickers: Vec<(T::Provider, T::Currency, Vec<u8>)>,
+		/// Seed best paths, as (source currency, target currency, total cost, steps of (source currency, target currency, provider, cost))
+		pub best_paths: Vec<(T::Currency, T::Currency, T::Amount, Vec<(T::Currency, T::Currency, T::Provider, T::Amount)>)>,
+	}
+
+	#[cfg(feature = "std")]
+	impl<T: Config> Default for GenesisConfig<T> {
+		fn default() -> Self {
+			Self {
+				monitored_pairs: vec![],
+				authorities: vec![],
+				currency_metadata: vec![],
+				provider_tickers: vec![],
+				best_paths: vec![],
+			}
+		}
+	}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig<T> {
+		fn build(&self) {
+			for (source, target, provider) in self.monitored_pairs.iter() {
+				MonitoredPairs::<T>::insert(ProviderPair{pair: Pair{source: source.clone(), target: target.clone()}, provider: provider.clone()}, PairOptions::default());
+			}
+			for account_id in self.authorities.iter() {
+				WhitelistedOffchainAuthorities::<T>::insert(account_id, ());
+			}
+			for (currency, metadata) in self.currency_metadata.iter() {
+				CurrencyMetadata::<T>::insert(currency, metadata);
+			}
+			for (provider, currency, ticker) in self.provider_tickers.iter() {
+				assert!(!ProviderTickerCurrencies::<T>::contains_key(provider, ticker), "Provider ticker mapped to multiple currencies");
+				assert!(!ProviderTickers::<T>::contains_key(provider, currency), "Currency mapped to multiple provider tickers");
+				ProviderTickers::<T>::insert(provider, currency, ticker);
+				ProviderTickerCurrencies::<T>::insert(provider, ticker, currency);
+			}
+			for (source, target, total_cost, steps) in self.best_paths.iter() {
+				let steps = steps.iter().map(|(step_source, step_target, provider, cost)| PathStep {
+					pair: Pair{source: step_source.clone(), target: step_target.clone()},
+					provider: provider.clone(),
+					cost: *cost,
+				}).collect::<Vec<_>>();
+				let step_quotes = steps.iter().map(|step| StepQuote{bid: step.cost, ask: step.cost}).collect();
+				BestPaths::<T>::insert(source, target, StoredPricePath{path: PricePath{total_cost: *total_cost, steps}, step_quotes, confidence: 0, updated_at: T::BlockNumber::default()});
+			}
+		}
 	}
 
 	#[pallet::pallet]
+	#[pallet::storage_version(STORAGE_VERSION)]
 	#[pallet::without_storage_info]
 	pub struct Pallet<T>(_);
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		/// Prunes expired `PathHistory` snapshots and held suspicious changes.
+		fn on_initialize(block_number: T::BlockNumber) -> Weight {
+			Self::prune_path_history(block_number).saturating_add(Self::prune_suspicious_changes(block_number))
+		}
+
+		/// Ensures OCW submissions may carry at least a single change.
+		fn integrity_test() {
+			assert!(T::MaxChangesPerTx::get() > 0, "MaxChangesPerTx must be positive");
+		}
+
+		/// Migrates storage up to `STORAGE_VERSION`.
+		fn on_runtime_upgrade() -> Weight {
+			migrations::migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			migrations::pre_migrate::<T>()
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			migrations::post_migrate::<T>()
+		}
+
 		/// Off-chain Worker entry point.
 		///
 		/// First checks whether can act upon this block, if so, attempts to obtain the lock, if successful, fetches and updates the best paths.
 		/// Once done, bumps the next trigger block storage by the delay amount, and lock is released.
 		fn offchain_worker(block_number: T::BlockNumber) {
+			if Paused::<T>::get() {
+				log::info!("OCW skipped, best path updates are paused");
+				return
+			}
 			if Self::should_trigger_offchain(block_number) {
 				// obtain the OCW lock
 				let lock_expiration = Duration::from_millis(OCW_LOCK_DURATION);
@@ -266,8 +677,15 @@ pub mod pallet {
 		/// Dedups by provider_pair, picking last operation only.
 		/// This call should only get through once its payload has been validated to be signed by a whitelisted authority.
 		/// Uses nonce for replay protection, bumping it upon the success.
+		/// Large change sets are split by the OCW into payloads of at most `MaxChangesPerTx` changes, applied in the nonce order.
+		/// Each payload is applied on its own, a failed one leaves the preceding ones applied, and the subsequent ones stale,
+		/// with the remaining changes resubmitted by the next OCW run.
 		/// Issues an event listing all supplied changes.
-		#[pallet::weight(T::WeightInfo::submit_monitored_pairs(best_path_change_payload.changes.len()))]
+		/// Each change is charged the `OnBestPathChanged` handler weight on top.
+		#[pallet::weight(T::WeightInfo::ocw_submit_best_paths_changes(
+			best_path_change_payload.changes.len() as u32,
+			best_path_change_payload.changes.iter().filter_map(|(_, _, path)| path.as_ref()).map(|path| path.steps.len() as u32).sum())
+			.saturating_add(T::OnBestPathChanged::on_best_path_changed_weight().saturating_mul(best_path_change_payload.changes.len() as Weight)))]
 		#[transactional]
 		pub fn ocw_submit_best_paths_changes(
 			origin: OriginFor<T>,
@@ -275,35 +693,52 @@ pub mod pallet {
 			_signature: T::Signature,
 		) -> DispatchResultWithPostInfo {
 			ensure_none(origin)?;
+			ensure!(!Paused::<T>::get(), Error::<T>::PalletPausedError);
 			let current_nonce = UnsignedTxNonce::<T>::get();
 			ensure!(current_nonce == best_path_change_payload.nonce, Error::<T>::StaleUnsignedTxError);
+			ensure!(best_path_change_payload.changes.len() <= T::MaxChangesPerTx::get() as usize, Error::<T>::TooManyChangesError);
 
+			let block_number = frame_system::Pallet::<T>::block_number();
+			let authority = best_path_change_payload.public.into_account();
+			let quotes: BTreeMap<_, _> = best_path_change_payload.step_quotes.into_iter().collect();
+			let confidences: BTreeMap<_, _> = best_path_change_payload.confidences.into_iter().map(|(source, target, confidence)| ((source, target), confidence)).collect();
 			let mut event_payload = vec![];
-			for (source, target, ref mut new_path) in best_path_change_payload.changes {
-				BestPaths::<T>::mutate_exists(
-					source.clone(),
-					target.clone(),
-					|old_path| {
-						match new_path.take() {
-							Some(path) => {
-								let total_cost = path.total_cost;
-								*old_path = Some(path);
-								log::info!("Onchain: adding/changing price onchain for {} -> {}: {:?}", source.to_str(), target.to_str(), total_cost);
-								event_payload.push((source, target, total_cost, Operation::Add));
-							}
-							None => if old_path.take().is_some() {
-								log::info!("Onchain: removing price onchain: {} -> {}", source.to_str(), target.to_str());
-								event_payload.push((source, target, T::Amount::default(), Operation::Del));
-							}
+			for (source, target, new_path) in best_path_change_payload.changes {
+				let affected_path = new_path.clone().or_else(|| BestPaths::<T>::get(&source, &target).map(|stored| stored.path));
+				if affected_path.map_or(false, |path| Self::uses_paused_provider(&path)) {
+					log::warn!("Onchain: ignoring price change via paused provider for {} -> {}", source.to_str(), target.to_str());
+					continue;
+				}
+				match new_path {
+					Some(path) => {
+						let stored = StoredPricePath{
+							step_quotes: Self::step_quotes(&path, &quotes),
+							confidence: confidences.get(&(source.clone(), target.clone())).copied().unwrap_or_default().min(MAX_CONFIDENCE),
+							path: path.clone(),
+							updated_at: block_number,
+						};
+						if Self::hold_if_suspicious(&source, &target, &stored) {
+							log::warn!("Onchain: holding suspicious price change for {} -> {}: {:?}", source.to_str(), target.to_str(), path.total_cost);
+							continue;
 						}
+						log::info!("Onchain: adding/changing price onchain for {} -> {}: {:?}", source.to_str(), target.to_str(), path.total_cost);
+						Self::store_best_path(&source, &target, stored, Some(&authority));
+						event_payload.push((source, target, Some(path), Operation::Add));
+					}
+					None => if Self::remove_best_path(&source, &target, block_number, &authority) {
+						log::info!("Onchain: removing price onchain: {} -> {}", source.to_str(), target.to_str());
+						event_payload.push((source, target, None, Operation::Del));
 					}
-				);
+				}
 			}
 
 			UnsignedTxNonce::<T>::set(current_nonce + 1);
 			// only issue event if mods were made
 			if !event_payload.is_empty() {
-				Self::deposit_event(Event::BestPricesSubmitted(event_payload));
+				Self::deposit_event(Event::BestPricesSubmitted(authority, event_payload));
+			}
+			if !best_path_change_payload.provider_outages.is_empty() {
+				Self::deposit_event(Event::ProviderOutagesReported(best_path_change_payload.provider_outages));
 			}
 			Ok(Pays::No.into())
 	    }
@@ -313,7 +748,8 @@ pub mod pallet {
 		/// Root operation, requires sudo.
 		/// Validates that all operations are mapped to a valid provider, then each operation is added/deleted to monitored pairs map.
 		/// Operations to be added are upserted, operations to be deleted are removed if exist, skipped otherwise.
-		#[pallet::weight(T::WeightInfo::submit_monitored_pairs(operations.len()))]
+		/// Bidirectional pairs have their inverse edges derived by the OCW, rather than fetched.
+		#[pallet::weight(T::WeightInfo::submit_monitored_pairs(operations.len() as u32))]
 		#[transactional]
 		pub fn submit_monitored_pairs(
 			origin: OriginFor<T>,
@@ -334,6 +770,232 @@ pub mod pallet {
 			WhitelistedOffchainAuthorities::<T>::insert(&offchain_authority, ());
 			Ok(())
 		}
+
+		/// Set or remove currency metadata.
+		///
+		/// Root operation, requires sudo.
+		/// Decimals are used to normalise fetched prices to native units, display names for display.
+		#[pallet::weight(T::WeightInfo::submit_currency_metadata())]
+		pub fn submit_currency_metadata(
+			origin: OriginFor<T>,
+			currency: T::Currency,
+			metadata: Option<CurrencyMeta>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let operation = if metadata.is_some() { Operation::Add } else { Operation::Del };
+			CurrencyMetadata::<T>::set(&currency, metadata);
+			Self::deposit_event(Event::CurrencyMetadataSubmitted(currency, operation));
+			Ok(())
+		}
+
+		/// Submit provider ticker adds/deletes.
+		///
+		/// Root operation, requires sudo.
+		/// `Some(ticker)` maps the currency to the provider ticker, replacing the previous mapping, `None` removes the mapping.
+		/// Fails if the ticker is already mapped to another currency of the same provider, as tickers are unique per provider.
+		/// The whole batch is validated upfront, and applied regardless of its order, hence tickers may be swapped between currencies.
+		/// Fails if a currency, or a ticker, is listed more than once for the same provider.
+		#[pallet::weight(T::WeightInfo::submit_provider_tickers(tickers.len() as u32))]
+		#[transactional]
+		pub fn submit_provider_tickers(
+			origin: OriginFor<T>,
+			tickers: Vec<(T::Provider, T::Currency, Option<Vec<u8>>)>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let mut currencies = BTreeSet::new();
+			let mut new_tickers = BTreeSet::new();
+			for (provider, currency, ticker) in tickers.iter() {
+				ensure!(currencies.insert((provider, currency)), Error::<T>::DuplicateProviderCurrencyError);
+				if let Some(ticker) = ticker {
+					ensure!(new_tickers.insert((provider, ticker)), Error::<T>::DuplicateProviderTickerError);
+				}
+			}
+			// tickers held by currencies outside of the batch remain taken
+			for &(provider, ticker) in new_tickers.iter() {
+				let holder = ProviderTickerCurrencies::<T>::get(provider, ticker);
+				ensure!(holder.map_or(true, |currency| currencies.contains(&(provider, &currency))), Error::<T>::DuplicateProviderTickerError);
+			}
+
+			// all old tickers are released before any new one is taken
+			for (provider, currency, _) in tickers.iter() {
+				if let Some(old_ticker) = ProviderTickers::<T>::take(provider, currency) {
+					ProviderTickerCurrencies::<T>::remove(provider, &old_ticker);
+				}
+			}
+			for (provider, currency, ticker) in tickers.iter() {
+				if let Some(ticker) = ticker {
+					ProviderTickers::<T>::insert(provider, currency, ticker);
+					ProviderTickerCurrencies::<T>::insert(provider, ticker, currency);
+				}
+			}
+
+			if !tickers.is_empty() {
+				Self::deposit_event(Event::ProviderTickersSubmitted(tickers));
+			}
+			Ok(())
+		}
+
+		/// Submit price change tolerance overrides of currency pairs.
+		///
+		/// Root operation, requires sudo.
+		/// `Some(tolerance)`, in 1/1,000,000, overrides the tolerance of the directional pair, `None` removes the override.
+		#[pallet::weight(T::WeightInfo::submit_pair_tolerances(tolerances.len() as u32))]
+		pub fn submit_pair_tolerances(
+			origin: OriginFor<T>,
+			tolerances: Vec<(T::Currency, T::Currency, Option<u32>)>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			for (source, target, tolerance) in tolerances.iter() {
+				PairTolerances::<T>::set(source, target, *tolerance);
+			}
+
+			if !tolerances.is_empty() {
+				Self::deposit_event(Event::PairTolerancesSubmitted(tolerances));
+			}
+			Ok(())
+		}
+
+		/// Submit currency group adds/deletes.
+		///
+		/// Root operation, requires sudo.
+		/// `Some(group)` assigns the currency to the group, eg. stablecoins, replacing its previous group, `None` removes it from its group.
+		#[pallet::weight(T::WeightInfo::submit_currency_groups(groups.len() as u32))]
+		pub fn submit_currency_groups(
+			origin: OriginFor<T>,
+			groups: Vec<(T::Currency, Option<Vec<u8>>)>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			for (currency, group) in groups.iter() {
+				CurrencyGroups::<T>::set(currency, group.clone());
+			}
+
+			if !groups.is_empty() {
+				Self::deposit_event(Event::CurrencyGroupsSubmitted(groups));
+			}
+			Ok(())
+		}
+
+		/// Submit price change tolerance overrides of currency groups.
+		///
+		/// Root operation, requires sudo.
+		/// `Some(tolerance)`, in 1/1,000,000, overrides the tolerance of pairs of the group currencies, `None` removes the override.
+		#[pallet::weight(T::WeightInfo::submit_group_tolerances(tolerances.len() as u32))]
+		pub fn submit_group_tolerances(
+			origin: OriginFor<T>,
+			tolerances: Vec<(Vec<u8>, Option<u32>)>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			for (group, tolerance) in tolerances.iter() {
+				GroupTolerances::<T>::set(group, *tolerance);
+			}
+
+			if !tolerances.is_empty() {
+				Self::deposit_event(Event::GroupTolerancesSubmitted(tolerances));
+			}
+			Ok(())
+		}
+
+		/// Approve a held suspicious best path change.
+		///
+		/// Root operation, requires sudo.
+		/// Writes the held path onchain and restarts the `PriceChangeWindow` from its cost.
+		/// Expired changes, ie. held more than `SuspiciousChangeTimeout` blocks ago, can no longer be approved.
+		/// Charged the `OnBestPathChanged` handler weight on top.
+		#[pallet::weight(T::WeightInfo::approve_suspicious_change().saturating_add(T::OnBestPathChanged::on_best_path_changed_weight()))]
+		pub fn approve_suspicious_change(
+			origin: OriginFor<T>,
+			source: T::Currency,
+			target: T::Currency) -> DispatchResult {
+			ensure_root(origin)?;
+
+			let block_number = frame_system::Pallet::<T>::block_number();
+			let held = PendingSuspiciousChanges::<T>::get(&source, &target)
+				.filter(|held| !Self::hold_expired(held.updated_at, block_number))
+				.ok_or(Error::<T>::SuspiciousChangeNotFoundError)?;
+			PendingSuspiciousChanges::<T>::remove(&source, &target);
+			Self::release_suspicious_change(source, target, held, block_number);
+			Ok(())
+		}
+
+		/// Reject a held suspicious best path change.
+		///
+		/// Root operation, requires sudo.
+		#[pallet::weight(T::WeightInfo::reject_suspicious_change())]
+		pub fn reject_suspicious_change(
+			origin: OriginFor<T>,
+			source: T::Currency,
+			target: T::Currency) -> DispatchResult {
+			ensure_root(origin)?;
+
+			PendingSuspiciousChanges::<T>::take(&source, &target).ok_or(Error::<T>::SuspiciousChangeNotFoundError)?;
+			Self::deposit_event(Event::SuspiciousChangeRejected(source, target));
+			Ok(())
+		}
+
+		/// Pause best path updates, globally if `provider` is None, otherwise for paths via the provider.
+		///
+		/// Root operation, requires sudo.
+		/// Emergency freeze switch, eg. in case of a compromised provider.
+		#[pallet::weight(T::WeightInfo::pause())]
+		pub fn pause(
+			origin: OriginFor<T>,
+			provider: Option<T::Provider>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			match provider {
+				Some(ref provider) => PausedProviders::<T>::insert(provider, ()),
+				None => Paused::<T>::set(true),
+			}
+			Self::deposit_event(Event::Paused(provider));
+			Ok(())
+		}
+
+		/// Unpause best path updates, globally if `provider` is None, otherwise for paths via the provider.
+		///
+		/// Root operation, requires sudo.
+		#[pallet::weight(T::WeightInfo::unpause())]
+		pub fn unpause(
+			origin: OriginFor<T>,
+			provider: Option<T::Provider>) -> DispatchResult {
+			ensure_root(origin)?;
+
+			match provider {
+				Some(ref provider) => PausedProviders::<T>::remove(provider),
+				None => Paused::<T>::set(false),
+			}
+			Self::deposit_event(Event::Unpaused(provider));
+			Ok(())
+		}
+
+		/// Execute a trade via the best path.
+		///
+		/// Walks the best path steps, dispatching each hop to the `Executors` adapter of the step provider.
+		/// Any failed hop, or the final amount below `min_amount_out`, reverts all the hops.
+		#[pallet::weight(T::WeightInfo::execute_best_path(T::MaxExecutionHops::get())
+			.saturating_add(T::Executors::execute_weight().saturating_mul(T::MaxExecutionHops::get() as Weight)))]
+		#[transactional]
+		pub fn execute_best_path(
+			origin: OriginFor<T>,
+			source: T::Currency,
+			target: T::Currency,
+			amount_in: T::Amount,
+			min_amount_out: T::Amount) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+			ensure!(!Paused::<T>::get(), Error::<T>::PalletPausedError);
+
+			let path = BestPaths::<T>::get(&source, &target).ok_or(Error::<T>::PricePairNotFoundError)?.path;
+			ensure!(!Self::uses_paused_provider(&path), Error::<T>::PalletPausedError);
+			ensure!(!path.steps.is_empty() && path.steps.len() <= T::MaxExecutionHops::get() as usize, Error::<T>::PathNotExecutableError);
+
+			let mut amount_out = amount_in;
+			for step in path.steps.iter() {
+				amount_out = T::Executors::execute_step(&who, &step.provider, &step.pair.source, &step.pair.target, amount_out)?;
+			}
+			ensure!(amount_out >= min_amount_out, Error::<T>::SlippageExceededError);
+
+			Self::deposit_event(Event::BestPathExecuted(who, source, target, amount_in, amount_out));
+			Ok(())
+		}
 	}
 
 	#[pallet::validate_unsigned]
@@ -352,6 +1014,10 @@ pub mod pallet {
 				signature,
 			} = call
 			{
+				if Paused::<T>::get() {
+					return InvalidTransaction::Call.into()
+				}
+
 				let signature_valid = SignedPayload::<T>::verify::<T::AuthorityId>(payload, signature.clone());
 				if !signature_valid {
 					log::error!("OCW rejected transaction due to invalid signature");
@@ -370,12 +1036,27 @@ pub mod pallet {
 					return InvalidTransaction::BadProof.into();
 				}
 
-				ValidTransaction::with_tag_prefix("BestPathWorker")
+				if payload.changes.len() > T::MaxChangesPerTx::get() as usize {
+					return InvalidTransaction::ExhaustsResources.into();
+				}
+
+				let current_nonce = UnsignedTxNonce::<T>::get();
+				if payload.nonce < current_nonce {
+					// already applied nonce, possibly a replay
+					return InvalidTransaction::Stale.into();
+				}
+
+				// chunks of the same change set are sequenced by their nonces, each awaiting its predecessor in the pool
+				let builder = ValidTransaction::with_tag_prefix("BestPathWorker")
 					.priority(T::UnsignedPriority::get())
-					.and_provides((TX_TAG, current_block))
+					.and_provides((TX_TAG, payload.nonce))
 					.longevity(5)  // transaction is only valid for next 5 blocks. After that it's revalidated by the pool.
-					.propagate(true)
-					.build()
+					.propagate(true);
+				if payload.nonce > current_nonce {
+					builder.and_requires((TX_TAG, payload.nonce - 1)).build()
+				} else {
+					builder.build()
+				}
 			} else {
 				InvalidTransaction::Call.into()
 			}
@@ -397,16 +1078,19 @@ impl<T: Config> Pallet<T> {
 
 		// add/delete monitored pairs
 		let mut event_payload = vec![];
-		for ProviderPairOperation{provider_pair, operation} in operations {
-			MonitoredPairs::<T>::mutate_exists(provider_pair.clone(), |exists_indicator| {
+		for ProviderPairOperation{provider_pair, operation, bidirectional} in operations {
+			MonitoredPairs::<T>::mutate_exists(provider_pair.clone(), |options| {
 				let ProviderPair { pair: Pair { source, target }, provider } = provider_pair;
 				match operation {
-					Operation::Add => if exists_indicator.is_none() {
-						*exists_indicator = Some(());
-						event_payload.push((source, target, provider, operation));
+					Operation::Add => {
+						let new_options = PairOptions { bidirectional };
+						if options.as_ref() != Some(&new_options) {
+							*options = Some(new_options);
+							event_payload.push((source, target, provider, operation));
+						}
 					},
 					Operation::Del => {
-						if exists_indicator.take().is_some() {
+						if options.take().is_some() {
 							event_payload.push((source, target, provider, operation));
 						}
 					},
@@ -420,6 +1104,208 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Circuit breaker of extreme price moves, bound by `MaxPriceChange` per update and `MaxWindowPriceChange` per `PriceChangeWindow`.
+	///
+	/// A breaching change is held in `PendingSuspiciousChanges`, and released once a subsequent OCW round, within `SuspiciousChangeTimeout` blocks,
+	/// submits a price within `MaxPriceChange` of the held one. Expired holds are replaced by the breaching change.
+	/// Returns true if the change is held, ie. should not be written to `BestPaths`.
+	fn hold_if_suspicious(source: &T::Currency, target: &T::Currency, stored: &StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber>) -> bool {
+		let StoredPricePath{path, updated_at: block_number, ..} = stored;
+		let block_number = *block_number;
+		let old_cost = match BestPaths::<T>::get(source, target) {
+			Some(old_path) => old_path.path.total_cost,
+			None => return false,  // nothing to compare against
+		};
+		let (window_start, window_cost) = match PriceWindowAnchors::<T>::get(source, target) {
+			Some((window_start, window_cost)) if block_number < window_start.saturating_add(T::PriceChangeWindow::get()) => (window_start, window_cost),
+			_ => (block_number, old_cost),
+		};
+
+		let new_cost = path.total_cost.saturated_into::<u128>();
+		let max_change = T::MaxPriceChange::get();
+		let breaches = breaches_tolerance(old_cost.saturated_into(), new_cost, max_change) ||
+			breaches_tolerance(window_cost.saturated_into(), new_cost, T::MaxWindowPriceChange::get());
+		if !breaches {
+			// price settled back, any held change is obsolete
+			PendingSuspiciousChanges::<T>::remove(source, target);
+			PriceWindowAnchors::<T>::insert(source, target, (window_start, window_cost));
+			return false
+		}
+
+		match PendingSuspiciousChanges::<T>::get(source, target) {
+			Some(StoredPricePath{path: held_path, updated_at: held_at, ..}) if held_at < block_number && !Self::hold_expired(held_at, block_number) && !breaches_tolerance(held_path.total_cost.saturated_into(), new_cost, max_change) => {
+				PendingSuspiciousChanges::<T>::remove(source, target);
+				Self::restart_price_window(source, target, path.total_cost, block_number);
+				Self::deposit_event(Event::SuspiciousChangeReleased(source.clone(), target.clone(), path.total_cost));
+				false
+			}
+			_ => {
+				PendingSuspiciousChanges::<T>::insert(source, target, stored);
+				let pair = (source.clone(), target.clone());
+				SuspiciousChangeExpiry::<T>::mutate(block_number.saturating_add(T::SuspiciousChangeTimeout::get()).saturating_add(One::one()), |pairs| if !pairs.contains(&pair) {
+					pairs.push(pair);
+				});
+				Self::deposit_event(Event::SuspiciousChangeHeld(source.clone(), target.clone(), old_cost, path.total_cost));
+				true
+			}
+		}
+	}
+
+	/// Determine if the change held at the block can no longer be confirmed nor approved, as per `SuspiciousChangeTimeout`
+	fn hold_expired(held_at: T::BlockNumber, block_number: T::BlockNumber) -> bool {
+		held_at.saturating_add(T::SuspiciousChangeTimeout::get()) < block_number
+	}
+
+	/// Prune `PendingSuspiciousChanges` expiring at the block, unless replaced by a more recent hold
+	fn prune_suspicious_changes(block_number: T::BlockNumber) -> Weight {
+		let expiring = SuspiciousChangeExpiry::<T>::take(block_number);
+		for (source, target) in expiring.iter() {
+			if PendingSuspiciousChanges::<T>::get(source, target).map_or(false, |held| Self::hold_expired(held.updated_at, block_number)) {
+				log::info!("Onchain: dropping expired suspicious change for {} -> {}", source.to_str(), target.to_str());
+				PendingSuspiciousChanges::<T>::remove(source, target);
+			}
+		}
+		let pairs = expiring.len() as Weight;
+		T::DbWeight::get().reads_writes(1 + pairs, 1 + pairs)
+	}
+
+	/// Determine if the path goes via any paused provider
+	fn uses_paused_provider(path: &PricePath<T::Currency, T::Amount, T::Provider>) -> bool {
+		path.steps.iter().any(|step| PausedProviders::<T>::contains_key(&step.provider))
+	}
+
+	/// Write the released held path onchain, as updated at the block of the release
+	fn release_suspicious_change(source: T::Currency, target: T::Currency, held: StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber>, block_number: T::BlockNumber) {
+		let total_cost = held.path.total_cost;
+		Self::store_best_path(&source, &target, StoredPricePath{updated_at: block_number, ..held}, None);
+		Self::restart_price_window(&source, &target, total_cost, block_number);
+		Self::deposit_event(Event::SuspiciousChangeReleased(source, target, total_cost));
+	}
+
+	/// Resolve the submitted bid/ask quotes of the path steps, defaulting to the step cost on both sides if not submitted
+	fn step_quotes(path: &PricePath<T::Currency, T::Amount, T::Provider>, quotes: &BTreeMap<ProviderPair<T::Currency, T::Provider>, StepQuote<T::Amount>>) -> Vec<StepQuote<T::Amount>> {
+		path.steps.iter()
+			.map(|step| quotes.get(&ProviderPair{pair: step.pair.clone(), provider: step.provider.clone()}).copied().unwrap_or(StepQuote{bid: step.cost, ask: step.cost}))
+			.collect()
+	}
+
+	/// Store the best path addition/change, archiving it in `PathHistory`, indexing it offchain and notifying `OnBestPathChanged`
+	fn store_best_path(source: &T::Currency, target: &T::Currency, stored: StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber>, authority: Option<&T::AccountId>) {
+		let block_number = stored.updated_at;
+		let old_path = BestPaths::<T>::get(source, target).map(|stored| stored.path);
+		BestPaths::<T>::insert(source, target, &stored);
+		Self::archive_best_path(source, target, &stored);
+		Self::observe_price(source, target, stored.path.total_cost, block_number);
+		Self::index_path_change(source, target, old_path.as_ref(), Some(&stored.path), block_number, authority);
+		T::OnBestPathChanged::on_best_path_changed(source, target, old_path.as_ref(), Some(&stored.path));
+	}
+
+	/// Remove the best path, if exists, along with its held suspicious change and price window, indexing the removal offchain and notifying `OnBestPathChanged`. Returns true if removed
+	fn remove_best_path(source: &T::Currency, target: &T::Currency, block_number: T::BlockNumber, authority: &T::AccountId) -> bool {
+		match BestPaths::<T>::take(source, target) {
+			Some(StoredPricePath{path: old_path, ..}) => {
+				TwapObservations::<T>::remove(source, target);
+				PendingSuspiciousChanges::<T>::remove(source, target);
+				PriceWindowAnchors::<T>::remove(source, target);
+				Self::index_path_change(source, target, Some(&old_path), None, block_number, Some(authority));
+				T::OnBestPathChanged::on_best_path_changed(source, target, Some(&old_path), None);
+				true
+			}
+			None => false,
+		}
+	}
+
+	/// Offchain index key of the path change record, unique per block, extrinsic and pair
+	pub fn path_change_index_key(block_number: T::BlockNumber, extrinsic_index: u32, source: &T::Currency, target: &T::Currency) -> Vec<u8> {
+		concat(&[PATH_CHANGE_INDEX_PREFIX, &(block_number, extrinsic_index, source, target).encode()])
+	}
+
+	/// Write the path change record to the offchain index, for external indexers
+	fn index_path_change(
+		source: &T::Currency,
+		target: &T::Currency,
+		old_path: Option<&PricePath<T::Currency, T::Amount, T::Provider>>,
+		new_path: Option<&PricePath<T::Currency, T::Amount, T::Provider>>,
+		block_number: T::BlockNumber,
+		authority: Option<&T::AccountId>) {
+		let providers = old_path.iter().chain(new_path.iter())
+			.flat_map(|path| path.steps.iter().map(|step| step.provider.clone()))
+			.collect::<BTreeSet<_>>()
+			.into_iter()
+			.collect();
+		let record = PathChangeRecord {
+			source: source.clone(),
+			target: target.clone(),
+			old_path: old_path.cloned(),
+			new_path: new_path.cloned(),
+			block_number,
+			providers,
+			authority: authority.cloned(),
+		};
+		let extrinsic_index = frame_system::Pallet::<T>::extrinsic_index().unwrap_or_default();
+		sp_io::offchain_index::set(&Self::path_change_index_key(block_number, extrinsic_index, source, target), &record.encode());
+	}
+
+	/// Append the snapshot to the `PathHistory` ring buffer of `PathHistoryDepth` snapshots, scheduling its expiry after `PathHistoryRetention` blocks
+	fn archive_best_path(source: &T::Currency, target: &T::Currency, stored: &StoredPricePath<T::Currency, T::Amount, T::Provider, T::BlockNumber>) {
+		let depth = T::PathHistoryDepth::get() as usize;
+		if depth == 0 {
+			return
+		}
+		PathHistory::<T>::mutate(source, target, |history| {
+			if history.len() >= depth {
+				history.drain(..=history.len() - depth);
+			}
+			history.push(stored.clone());
+		});
+
+		let retention = T::PathHistoryRetention::get();
+		if !retention.is_zero() {
+			let pair = (source.clone(), target.clone());
+			PathHistoryExpiry::<T>::mutate(stored.updated_at.saturating_add(retention), |pairs| if !pairs.contains(&pair) {
+				pairs.push(pair);
+			});
+		}
+	}
+
+	/// Record the cumulative price observation, as of the price change
+	fn observe_price(source: &T::Currency, target: &T::Currency, price: T::Amount, block_number: T::BlockNumber) {
+		let max_observations = T::MaxTwapObservations::get() as usize;
+		TwapObservations::<T>::mutate(source, target, |observations| {
+			let cumulative = observations.last().map_or(0, |last| last.cumulative_at(block_number));
+			if observations.last().map_or(false, |last| last.block_number == block_number) {
+				observations.pop();  // superseded within the same block
+			}
+			observations.push(TwapObservation{block_number, cumulative, price: price.saturated_into()});
+			if observations.len() > max_observations {
+				observations.drain(..observations.len() - max_observations);
+			}
+		});
+	}
+
+	/// Prune `PathHistory` snapshots expiring at the block
+	fn prune_path_history(block_number: T::BlockNumber) -> Weight {
+		let expiring = PathHistoryExpiry::<T>::take(block_number);
+		let retention = T::PathHistoryRetention::get();
+		for (source, target) in expiring.iter() {
+			PathHistory::<T>::mutate_exists(source, target, |history| {
+				if let Some(snapshots) = history {
+					snapshots.retain(|snapshot| snapshot.updated_at.saturating_add(retention) > block_number);
+					if snapshots.is_empty() {
+						*history = None;
+					}
+				}
+			});
+		}
+		let pairs = expiring.len() as Weight;
+		T::DbWeight::get().reads_writes(1 + pairs, 1 + pairs)
+	}
+
+	/// Restart `PriceChangeWindow` from the released cost, so that the accepted move does not count against the window
+	fn restart_price_window(source: &T::Currency, target: &T::Currency, cost: T::Amount, block_number: T::BlockNumber) {
+		PriceWindowAnchors::<T>::insert(source, target, (block_number, cost));
+	}
+
 	/// Determine if can trigger OCW based on the next offchain trigger block delay mechanism
 	fn should_trigger_offchain(block_number: T::BlockNumber) -> bool {
 		match StorageValueRef::persistent(NEXT_OFFCHAIN_TRIGGER_BLOCK).get::<T::BlockNumber>() {
@@ -444,34 +1330,436 @@ impl<T: Config> Pallet<T> {
 
 	/// A helper function to fetch the price, sign payload and send an unsigned transaction
 	fn fetch_prices_and_update_best_paths(block_number: T::BlockNumber) -> Result<(), String> {
-		let fetched_pairs = MonitoredPairs::<T>::iter_keys()
-			.filter_map(|pp| {
-				T::PriceProviderHub::get_price(&pp.provider, &pp.pair.source, &pp.pair.target).ok().map(|res| (pp, res))
+		let started = sp_io::offchain::timestamp();
+		let (fetched_quotes, provider_outages) = Self::fetch_prices(block_number);
+		let edge_quotes = Self::derive_inverse_pairs(Self::aggregate_prices(fetched_quotes));
+		let provider_outages = if T::ReportProviderOutages::get() { provider_outages } else { vec![] };
+
+		// edges are weighted by their bids, ie. selling the source currency
+		let fetched_pairs = edge_quotes.iter().map(|(pp, quote, _)| (pp.clone(), quote.bid)).collect::<Vec<_>>();
+		let changes = if fetched_pairs.is_empty() {
+			log::debug!("Offchain: no price pairs to update!");
+			vec![]
+		} else {
+			Self::calc_best_path_changes(&fetched_pairs, block_number)?
+		};
+		let edge_quotes: BTreeMap<_, _> = edge_quotes.into_iter().map(|(pp, quote, confidence)| (pp, (quote, confidence))).collect();
+
+		if changes.is_empty() && provider_outages.is_empty() {
+			log::info!("Offchain: detected no price changes that breached tolerance level")
+		} else {
+			let mut chunks = Self::chunk_changes(changes, &edge_quotes, T::MaxChangesPerTx::get());
+			if chunks.is_empty() {
+				// outages only
+				chunks.push((vec![], vec![], vec![]));
+			}
+			let chunk_count = chunks.len();
+			let first_nonce = UnsignedTxNonce::<T>::get();
+			for (i, (changes, step_quotes, confidences)) in chunks.into_iter().enumerate() {
+				// outages are reported once, along with the first chunk
+				let provider_outages = if i == 0 { provider_outages.clone() } else { vec![] };
+				let (_, result) = Signer::<T, T::AuthorityId>::any_account()
+					.send_unsigned_transaction(
+						|account| BestPathChangesPayload {
+							changes: changes.clone(),
+							step_quotes: step_quotes.clone(),
+							confidences: confidences.clone(),
+							provider_outages: provider_outages.clone(),
+							nonce: first_nonce + i as u64,
+							block_number,
+							public: account.public.clone()
+						},
+						|payload, signature| Call::ocw_submit_best_paths_changes {
+							best_path_change_payload: payload,
+							signature,
+						},
+					)
+					.ok_or("No local accounts accounts available")?;
+				result.map_err(|()| format!("Unable to submit transaction {} of {}", i + 1, chunk_count))?;
+			}
+
+			log::info!("Offchain: updated best paths in {} transaction(s)!", chunk_count);
+		}
+
+		let elapsed = sp_io::offchain::timestamp().diff(&started).millis();
+		let budget = T::OcwTimeBudget::get();
+		if budget > 0 && elapsed > budget {
+			log::warn!("Offchain: best path update took {}ms, exceeding the time budget of {}ms", elapsed, budget);
+		} else {
+			log::debug!("Offchain: best path update took {}ms", elapsed);
+		}
+		Ok(())
+	}
+
+	/// Split best path changes into chunks of at most `max_changes`, preserving their order.
+	///
+	/// Each chunk carries the step quotes and confidences of its own paths only. Paths are as reliable as their least confident edge,
+	/// those without recorded steps are of unknown confidence, ie. 0, which is the onchain default, hence left out of the confidences,
+	/// while the paths themselves are still submitted.
+	fn chunk_changes(
+		changes: Vec<(T::Currency, T::Currency, Option<PricePath<T::Currency, T::Amount, T::Provider>>)>,
+		edge_quotes: &BTreeMap<ProviderPair<T::Currency, T::Provider>, (StepQuote<T::Amount>, u32)>,
+		max_changes: u32,
+	) -> Vec<ChangesChunk<T::Currency, T::Amount, T::Provider>> {
+		changes.chunks(max_changes as usize)
+			.map(|changes| {
+				let confidences = changes.iter()
+					.filter_map(|(source, target, path)| path.as_ref().map(|path| (source.clone(), target.clone(), path.steps.iter()
+						.map(|step| edge_quotes.get(&ProviderPair{pair: step.pair.clone(), provider: step.provider.clone()}).map_or(0, |(_, confidence)| *confidence))
+						.min()
+						.unwrap_or_default())))
+					.filter(|(_, _, confidence)| *confidence > 0)
+					.collect::<Vec<_>>();
+				let step_quotes = changes.iter()
+					.flat_map(|(_, _, path)| path.iter().flat_map(|path| path.steps.iter()))
+					.map(|step| ProviderPair{pair: step.pair.clone(), provider: step.provider.clone()})
+					.collect::<BTreeSet<_>>()
+					.into_iter()
+					.filter_map(|pp| edge_quotes.get(&pp).map(|(quote, _)| (pp, *quote)))
+					.collect::<Vec<_>>();
+				(changes.to_vec(), step_quotes, confidences)
 			})
-			.collect::<Vec<(_, _)>>();
+			.collect()
+	}
 
-		if fetched_pairs.is_empty() {
-			log::debug!("Offchain: no price pairs to update!");
-			return Ok(())
+	/// Fetch prices of all monitored pairs, skipping providers deemed unhealthy.
+	///
+	/// Tracks consecutive failures per provider in the offchain storage. Once `ProviderFailureThreshold` is reached,
+	/// the provider is skipped for `ProviderCooldown` blocks, and reported in the returned outages.
+	///
+	/// Pairs are fetched least recently attempted first, within the `OcwTimeBudget`. Pairs left over once the budget is exhausted
+	/// are priced from their latest fetch, if successful and within `MaxCachedQuoteAge`, as kept in the offchain `PRICE_CACHE`,
+	/// and resumed first on the next trigger. Pairs of the `DexProvider` are read from the onchain pools regardless of the budget.
+	/// Pairs of paused or unhealthy providers are skipped either way.
+	fn fetch_prices(block_number: T::BlockNumber) -> (Vec<(ProviderPair<T::Currency, T::Provider>, PriceQuote<T::Amount>)>, Vec<T::Provider>) {
+		let started = sp_io::offchain::timestamp();
+		let budget = T::OcwTimeBudget::get();
+		let cache_ref = StorageValueRef::persistent(PRICE_CACHE);
+		let mut cache: BTreeMap<ProviderPair<T::Currency, T::Provider>, CachedQuote<T::Amount, T::BlockNumber>> = cache_ref.get().ok().flatten().unwrap_or_default();
+		let mut pairs = MonitoredPairs::<T>::iter_keys().collect::<Vec<_>>();
+		// never attempted pairs first, stable sort retains the storage order otherwise
+		pairs.sort_by_key(|pp| cache.get(pp).map(|cached| cached.attempted_at));
+		let monitored = pairs.iter().cloned().collect::<BTreeSet<_>>();
+		cache.retain(|pp, _| monitored.contains(pp));
+		// stale quotes are discarded, while their attempts still order the fetches
+		let max_age = T::MaxCachedQuoteAge::get();
+		for cached in cache.values_mut() {
+			if block_number.saturating_sub(cached.attempted_at) > max_age {
+				cached.quote = None;
+			}
+		}
+
+		let mut healths = BTreeMap::new();
+		let mut fetched_pairs = vec![];
+		let mut provider_outages = vec![];
+		let mut failures = vec![];
+		let mut out_of_budget = 0;
+		for pp in pairs {
+			let health = healths.entry(pp.provider.clone()).or_insert_with(|| Self::provider_health(&pp.provider));
+			if PausedProviders::<T>::contains_key(&pp.provider) {
+				log::debug!("Offchain: skipping paused provider {:?} for {} -> {}", pp.provider, pp.pair.source.to_str(), pp.pair.target.to_str());
+				continue;
+			}
+			if health.is_skipped(block_number) {
+				log::debug!("Offchain: skipping unhealthy provider {:?} for {} -> {}", pp.provider, pp.pair.source.to_str(), pp.pair.target.to_str());
+				continue;
+			}
+			// onchain pool reads are cheap, the budget bounds the HTTP queries only
+			let is_dex = T::DexProvider::get().as_ref() == Some(&pp.provider);
+			if !is_dex && budget > 0 && (out_of_budget > 0 || sp_io::offchain::timestamp().diff(&started).millis() >= budget) {
+				out_of_budget += 1;
+				if let Some(quote) = cache.get(&pp).and_then(|cached| cached.quote.clone()) {
+					fetched_pairs.push((pp, quote));
+				}
+				continue;
+			}
+			let fetched = Self::fetch_price_with_retries(&pp);
+			cache.insert(pp.clone(), CachedQuote { attempted_at: block_number, quote: fetched.as_ref().ok().cloned() });
+			match fetched {
+				Ok(quote) => {
+					*health = ProviderHealth::default();
+					fetched_pairs.push((pp, quote));
+				}
+				Err(e) => {
+					log::debug!("Offchain: failed to fetch price for {} -> {} from {:?} due to {:?}", pp.pair.source.to_str(), pp.pair.target.to_str(), pp.provider, e);
+					health.consecutive_failures = health.consecutive_failures.saturating_add(1);
+					if health.consecutive_failures >= T::ProviderFailureThreshold::get() {
+						if health.skip_until.is_none() {
+							log::warn!("Offchain: provider {:?} deemed unhealthy after {} consecutive failures", pp.provider, health.consecutive_failures);
+							provider_outages.push(pp.provider.clone());
+						}
+						health.skip_until = Some(block_number + T::ProviderCooldown::get());
+					}
+					failures.push((pp, e));
+				}
+			}
+		}
+
+		for (provider, health) in healths.iter() {
+			StorageValueRef::persistent(&Self::provider_health_key(provider)).set(health);
+		}
+		Self::record_fetch_failures(&failures);
+		cache_ref.set(&cache);
+		if out_of_budget > 0 {
+			log::warn!("Offchain: time budget of {}ms exhausted, {} pairs priced from their latest fetch, to be fetched on the next trigger", budget, out_of_budget);
+		}
+
+		(fetched_pairs, provider_outages)
+	}
+
+	/// Fetch the price of a single provider pair, retrying upon failure with an exponential backoff.
+	///
+	/// Pairs of the `DexProvider` are instead priced from onchain pools.
+	fn fetch_price_with_retries(provider_pair: &ProviderPair<T::Currency, T::Provider>) -> Result<PriceQuote<T::Amount>, PriceProviderErr> {
+		let ProviderPair { pair: Pair { source, target }, provider } = provider_pair;
+		if T::DexProvider::get().as_ref() == Some(provider) {
+			return match T::DexPriceSource::get_pool_price(source, target, Self::currency_decimals(source), Self::currency_decimals(target), T::PriceScale::get()) {
+				Some(quote) if quote.price.is_zero() => Err(PriceProviderErr::InvalidPriceErr),
+				Some(quote) => Ok(quote),
+				None => Err(PriceProviderErr::UnsupportedPairErr),
+			}
+		}
+
+		let source_ticker = Self::provider_ticker(provider, source);
+		let target_ticker = Self::provider_ticker(provider, target);
+		let query = PriceQuery {
+			source: &source_ticker,
+			target: &target_ticker,
+			scale: T::PriceScale::get(),
+		};
+
+		let mut backoff = T::PriceFetchBackoff::get();
+		let mut attempt = 0;
+		loop {
+			match T::PriceProviderHub::get_price(provider, &query) {
+				Err(e) if e.is_transient() && attempt < T::PriceFetchRetries::get() => {
+					log::debug!("Offchain: retrying price fetch for {} -> {} in {}ms due to {:?}", source.to_str(), target.to_str(), backoff, e);
+					sp_io::offchain::sleep_until(sp_io::offchain::timestamp().add(Duration::from_millis(backoff)));
+					backoff = backoff.saturating_mul(2);
+					attempt += 1;
+				}
+				res => return res,
+			}
+		}
+	}
+
+	/// Aggregate the same pair prices across providers, as per `PriceAggregation` method, discarding outliers.
+	///
+	/// Aggregated price is attributed to the provider with the closest price, so that each pair is represented by a single edge,
+	/// quoted at the bid/ask of that provider, shifted by the difference of the aggregated and the provider price.
+	/// Each edge carries the confidence of its price, as per the agreement and dispersion of the providers.
+	fn aggregate_prices(fetched_quotes: Vec<(ProviderPair<T::Currency, T::Provider>, PriceQuote<T::Amount>)>) -> Vec<(ProviderPair<T::Currency, T::Provider>, StepQuote<T::Amount>, u32)> {
+		let method = T::PriceAggregation::get();
+		let max_deviation = T::MaxProviderDeviation::get();
+		let full_confidence_providers = T::FullConfidenceProviders::get();
+		if method == AggregationMethod::None {
+			return fetched_quotes.into_iter()
+				.filter_map(|(pp, quote)| {
+					let quote = quote.map(|a| a.saturated_into());
+					let confidence = confidence(&[quote.price], 1, quote.price, max_deviation, full_confidence_providers);
+					Self::step_quote(&quote).map(|quote| (pp, quote, confidence))
+				})
+				.collect()
+		}
+
+		let mut quotes_by_pair: BTreeMap<Pair<T::Currency>, Vec<(T::Provider, PriceQuote<u128>)>> = BTreeMap::new();
+		for (ProviderPair { pair, provider }, quote) in fetched_quotes {
+			quotes_by_pair.entry(pair).or_default().push((provider, quote.map(|a| a.saturated_into())));
+		}
+
+		let mut aggregated = vec![];
+		for (pair, quotes) in quotes_by_pair {
+			match aggregate_quotes(method, max_deviation, &quotes) {
+				Some(Aggregate { price, provider, discarded }) => {
+					if !discarded.is_empty() {
+						log::warn!("Offchain: discarded outlier prices for {} -> {} from providers: {:?}", pair.source.to_str(), pair.target.to_str(), discarded);
+					}
+					let quote = quotes.iter().find(|(p, _)| p == &provider).map(|(_, quote)| PriceQuote {
+						price,
+						bid: quote.bid.and_then(|bid| rescale(bid, quote.price, price)),
+						ask: quote.ask.and_then(|ask| rescale(ask, quote.price, price)),
+						..quote.clone()
+					});
+					let accepted = quotes.iter().filter(|(p, _)| !discarded.contains(p)).map(|(_, quote)| quote.price).collect::<Vec<_>>();
+					let confidence = confidence(&accepted, quotes.len(), price, max_deviation, full_confidence_providers);
+					match quote.as_ref().and_then(Self::step_quote) {
+						Some(quote) => aggregated.push((ProviderPair { pair, provider }, quote, confidence)),
+						None => log::warn!("Offchain: unable to quote aggregated price for {} -> {}: {:?}", pair.source.to_str(), pair.target.to_str(), price),
+					}
+				}
+				None => log::warn!("Offchain: no price consensus for {} -> {} amongst: {:?}", pair.source.to_str(), pair.target.to_str(), quotes),
+			}
+		}
+		aggregated
+	}
+
+	/// Both sides of the edge, defaulting to `DefaultSpread` around the price if the bid or ask isn't quoted, or the quoted book is crossed
+	fn step_quote(quote: &PriceQuote<u128>) -> Option<StepQuote<T::Amount>> {
+		let (default_bid, default_ask) = spread_quote(quote.price, T::DefaultSpread::get());
+		let (bid, ask) = match (quote.bid.unwrap_or(default_bid), quote.ask.unwrap_or(default_ask)) {
+			(bid, ask) if bid <= ask => (bid, ask),
+			_ => (default_bid, default_ask),
+		};
+		match (T::Amount::try_from(bid), T::Amount::try_from(ask)) {
+			(Ok(bid), Ok(ask)) if !bid.is_zero() => Some(StepQuote { bid, ask }),
+			_ => None,
+		}
+	}
+
+	/// Whether the inverse edge of the monitored pair is to be derived, as per `DeriveInversePairs` or the pair's `bidirectional` option
+	fn derives_inverse(provider_pair: &ProviderPair<T::Currency, T::Provider>) -> bool {
+		MonitoredPairs::<T>::get(provider_pair).map_or(false, |options| T::DeriveInversePairs::get() || options.bidirectional)
+	}
+
+	/// Extend the fetched quotes with inverse edges of bidirectional pairs. Selling the target currency is buying the source,
+	/// hence the inverse bid is priced off the ask at `10^(2*PriceScale) / ask`, less `InversePairHaircut`, and the inverse ask off the bid.
+	///
+	/// Inverse edges are skipped if the same provider's inverse price has been fetched, and share the confidence of the fetched edges.
+	fn derive_inverse_pairs(fetched_quotes: Vec<(ProviderPair<T::Currency, T::Provider>, StepQuote<T::Amount>, u32)>) -> Vec<(ProviderPair<T::Currency, T::Provider>, StepQuote<T::Amount>, u32)> {
+		let fetched: BTreeSet<_> = fetched_quotes.iter().map(|(pp, _, _)| pp.clone()).collect();
+		let scale = T::PriceScale::get();
+		let inverse = |price: T::Amount, haircut| inverse_price(price.saturated_into(), scale, haircut).and_then(|p| T::Amount::try_from(p).ok()).filter(|p| !p.is_zero());
+		let mut inverse_quotes = vec![];
+		for (pp, quote, confidence) in fetched_quotes.iter() {
+			let inverse_pp = ProviderPair { pair: Pair { source: pp.pair.target.clone(), target: pp.pair.source.clone() }, provider: pp.provider.clone() };
+			if fetched.contains(&inverse_pp) || !Self::derives_inverse(pp) {
+				continue;
+			}
+			match (inverse(quote.ask, T::InversePairHaircut::get()), inverse(quote.bid, 0)) {
+				(Some(bid), Some(ask)) => inverse_quotes.push((inverse_pp, StepQuote { bid, ask }, *confidence)),
+				_ => log::warn!("Offchain: unable to derive inverse price for {} -> {} from {:?}", pp.pair.source.to_str(), pp.pair.target.to_str(), quote),
+			}
+		}
+		[fetched_quotes, inverse_quotes].concat()
+	}
+
+	/// Resolve the currency ticker for the provider, defaulting to the currency itself
+	pub fn provider_ticker(provider: &T::Provider, currency: &T::Currency) -> Vec<u8> {
+		ProviderTickers::<T>::get(provider, currency).unwrap_or_else(|| currency.as_ref().to_vec())
+	}
+
+	/// Decimals of the currency native unit, 0 if metadata isn't registered.
+	///
+	/// Fetched prices, and hence best path costs, are normalised to native units, ie. `amount_in * total_cost / 10^PriceScale` quotes
+	/// target native units for the `amount_in` of source native units.
+	pub fn currency_decimals(currency: &T::Currency) -> u8 {
+		CurrencyMetadata::<T>::get(currency).map_or(0, |m| m.decimals)
+	}
+
+	/// Log the summary of failed pairs, and bump the failure counters by kind in the offchain storage
+	fn record_fetch_failures(failures: &[(ProviderPair<T::Currency, T::Provider>, PriceProviderErr)]) {
+		if failures.is_empty() {
+			return;
+		}
+
+		let summary = failures.iter()
+			.map(|(pp, e)| format!(
+				"{} -> {} ({:?}): {:?}",
+				pp.pair.source.to_display_str(CurrencyMetadata::<T>::get(&pp.pair.source).as_ref()),
+				pp.pair.target.to_display_str(CurrencyMetadata::<T>::get(&pp.pair.target).as_ref()),
+				pp.provider,
+				e))
+			.collect::<Vec<_>>();
+		log::warn!("Offchain: failed to fetch {} price pair(s): {}", failures.len(), summary.join(", "));
+
+		let mut stats = Self::fetch_failure_stats();
+		for (_, e) in failures {
+			let count = stats.entry(e.kind().to_vec()).or_insert(0);
+			*count = count.saturating_add(1);
+		}
+		StorageValueRef::persistent(FETCH_FAILURE_STATS).set(&stats);
+	}
+
+	/// Read the cumulative counters of price fetch failures, by failure kind
+	pub fn fetch_failure_stats() -> BTreeMap<Vec<u8>, u64> {
+		StorageValueRef::persistent(FETCH_FAILURE_STATS).get().ok().flatten().unwrap_or_default()
+	}
+
+	fn provider_health_key(provider: &T::Provider) -> Vec<u8> {
+		concat(&[PROVIDER_HEALTH_PREFIX, &provider.encode()])
+	}
+
+	/// Read the provider health from the offchain storage, defaulting to healthy
+	fn provider_health(provider: &T::Provider) -> ProviderHealth<T::BlockNumber> {
+		StorageValueRef::persistent(&Self::provider_health_key(provider)).get().ok().flatten().unwrap_or_default()
+	}
+
+	/// Verify storage invariants, from tests and from the try-runtime `post_upgrade`, as polkadot-v0.9.24 has no `try_state` hook:
+	/// - best path confidence does not exceed `MAX_CONFIDENCE`, step quotes, if any, are one per step and not crossed
+	/// - best path steps reference monitored pairs (or inverses of bidirectional ones) only, are connected from source to target, and multiply up to the total cost (within `PriceChangeTolerance`)
+	/// - whitelisted authorities decode to authority keys of the pallet `crypto`, which are valid sr25519 keys, ie. ristretto points, as no other account could sign a payload
+	///
+	/// Paths without steps are not verified, as not all best path calculators record them.
+	#[cfg(any(feature = "try-runtime", test))]
+	pub fn do_try_state() -> Result<(), &'static str> {
+		for authority in WhitelistedOffchainAuthorities::<T>::iter_keys() {
+			let key = crate::crypto::Public::decode_all(&mut &authority.encode()[..]).map_err(|_| "Whitelisted authority does not decode to an authority key")?;
+			ensure!(schnorrkel::PublicKey::from_bytes(key.as_ref()).is_ok(), "Whitelisted authority is not a valid sr25519 key");
+		}
+
+		let scale = 10_u128.checked_pow(T::PriceScale::get()).ok_or("PriceScale overflow")?;
+		for (source, target, StoredPricePath{path, step_quotes, confidence, ..}) in BestPaths::<T>::iter() {
+			ensure!(confidence <= MAX_CONFIDENCE, "Best path confidence exceeds full confidence");
+			ensure!(step_quotes.is_empty() || step_quotes.len() == path.steps.len(), "Best path step quotes do not match its steps");
+			ensure!(step_quotes.iter().all(|quote| quote.bid <= quote.ask), "Best path step quote bid exceeds ask");
+			if path.steps.is_empty() {
+				continue;
+			}
+			let mut step_source = &source;
+			let mut steps_cost: Option<u128> = None;
+			for step in path.steps.iter() {
+				let inverse_pp = ProviderPair{pair: Pair{source: step.pair.target.clone(), target: step.pair.source.clone()}, provider: step.provider.clone()};
+				ensure!(MonitoredPairs::<T>::contains_key(ProviderPair{pair: step.pair.clone(), provider: step.provider.clone()}) || Self::derives_inverse(&inverse_pp), "Best path step references unmonitored pair");
+				ensure!(&step.pair.source == step_source, "Best path steps not connected");
+				step_source = &step.pair.target;
+				let cost = step.cost.saturated_into::<u128>();
+				steps_cost = Some(match steps_cost {
+					Some(acc) => acc.checked_mul(cost).ok_or("Best path steps cost overflow")? / scale,
+					None => cost,
+				});
+			}
+			ensure!(step_source == &target, "Best path steps do not reach the target");
+			ensure!(!breaches_tolerance(steps_cost.unwrap_or_default(), path.total_cost.saturated_into(), T::PriceChangeTolerance::get()), "Best path total cost does not match its steps");
 		}
 
-		let new_best_paths = T::BestPathCalculator::calc_best_paths(&fetched_pairs).map_err(|e| format!("Failed to calculate best prices due to {:?}", e))?;
+		Ok(())
+	}
+
+	/// Price change tolerance of the pair, expressed in 1/1,000,000.
+	///
+	/// The pair override in `PairTolerances` if any, otherwise the looser of the source and target currency tolerances,
+	/// ie. those of their groups in `GroupTolerances`, defaulting to `PriceChangeTolerance`.
+	fn price_change_tolerance(source: &T::Currency, target: &T::Currency) -> u32 {
+		PairTolerances::<T>::get(source, target).unwrap_or_else(|| {
+			let currency_tolerance = |currency: &T::Currency| CurrencyGroups::<T>::get(currency).and_then(GroupTolerances::<T>::get).unwrap_or_else(T::PriceChangeTolerance::get);
+			currency_tolerance(source).max(currency_tolerance(target))
+		})
+	}
+
+	/// Calculate best paths from the fetched prices, and select the differences with currently stored best paths
+	fn calc_best_path_changes(fetched_pairs: &[(ProviderPair<T::Currency, T::Provider>, T::Amount)], block_number: T::BlockNumber) -> Result<Vec<(T::Currency, T::Currency, Option<PricePath<T::Currency, T::Amount, T::Provider>>)>, String> {
+		let new_best_paths = T::BestPathCalculator::calc_best_paths(fetched_pairs).map_err(|e| format!("Failed to calculate best prices due to {:?}", e))?;
 
 		// select the best path differences
-		// - elements changed at all and outside of acceptable tolerance
+		// - elements changed at all and outside of acceptable tolerance, per pair
+		// - elements older than `MaxPathAge`, regardless of the tolerance
 		// - no longer existing elements
 		// - newly added elements
 		let mut changes = vec![];
-		let tolerance = T::PriceChangeTolerance::get();
-		for (source, target, old_price_path) in BestPaths::<T>::iter() {  // FIXME: iterating over *all* of BestPaths...
+		let max_age = T::MaxPathAge::get();
+		for (source, target, StoredPricePath{path: old_price_path, updated_at, ..}) in BestPaths::<T>::iter() {  // FIXME: iterating over *all* of BestPaths...
 			let pair = Pair{ source: source.clone(), target: target.clone() };
 			match new_best_paths.get(&pair) {
 				Some(new_price_path) => {
 					let old_total_cost: u128 = old_price_path.total_cost.try_into().map_err(|_| "failed to convert old_price_path.total_cost")?;
 					let new_total_cost: u128 = new_price_path.total_cost.try_into().map_err(|_| "failed to convert new_price_path.total_cost")?;
+					let tolerance = Self::price_change_tolerance(&source, &target);
 					if breaches_tolerance(old_total_cost, new_total_cost, tolerance) {
 						log::debug!("Offchain: adding price change for {:?} -> {:?} in excess of tolerance: {:?}: {:?} -> {:?}", pair.source.to_str(), pair.target.to_str(), tolerance, old_total_cost, new_total_cost);
 						changes.push((source, target, Some(new_price_path.clone())));
+					} else if !max_age.is_zero() && block_number.saturating_sub(updated_at) >= max_age {
+						log::debug!("Offchain: adding price change for {:?} -> {:?} last updated at {:?}, exceeding max age of {:?}: {:?} -> {:?}", pair.source.to_str(), pair.target.to_str(), updated_at, max_age, old_total_cost, new_total_cost);
+						changes.push((source, target, Some(new_price_path.clone())));
 					} else {
 						log::debug!("Offchain: skipping price change for {:?} -> {:?} within tolerance of {:?}: {:?} -> {:?}", pair.source.to_str(), pair.target.to_str(), tolerance, old_total_cost, new_total_cost);
 					}
@@ -486,24 +1774,7 @@ impl<T: Config> Pallet<T> {
 			}
 		}
 
-		if changes.is_empty() {
-			log::info!("Offchain: detected no price changes that breached tolerance level")
-		} else {
-			let (_, result) = Signer::<T, T::AuthorityId>::any_account()
-				.send_unsigned_transaction(
-					|account| BestPathChangesPayload {changes: changes.clone(), nonce: UnsignedTxNonce::<T>::get(), block_number, public: account.public.clone() },
-					|payload, signature| Call::ocw_submit_best_paths_changes {
-						best_path_change_payload: payload,
-						signature,
-					},
-				)
-				.ok_or("No local accounts accounts available")?;
-			result.map_err(|()| "Unable to submit transaction")?;
-
-			log::info!("Offchain: updated best paths!");
-		}
-
-		Ok(())
+		Ok(changes)
 	}
 }
 
@@ -512,6 +1783,63 @@ impl<T: Config> BestPathTrait<T::Currency, T::Amount, T::Provider> for Pallet<T>
 		Self::do_submit_monitored_pairs(operations);
 	}
     fn get_price_path(source: T::Currency, target: T::Currency) -> Option<PricePath<T::Currency, T::Amount, T::Provider>> {
-		BestPaths::<T>::get(&source, &target)
+		let path = BestPaths::<T>::get(&source, &target)?.path;
+		if T::PausedHidesPaths::get() && (Paused::<T>::get() || Self::uses_paused_provider(&path)) {
+			return None
+		}
+		Some(path)
+	}
+    fn confidence(source: T::Currency, target: T::Currency) -> Option<u32> {
+		let stored = BestPaths::<T>::get(&source, &target)?;
+		if T::PausedHidesPaths::get() && (Paused::<T>::get() || Self::uses_paused_provider(&stored.path)) {
+			return None
+		}
+		let age = frame_system::Pallet::<T>::block_number().saturating_sub(stored.updated_at);
+		Some(decay_confidence(stored.confidence, age.saturated_into(), T::ConfidenceDecayPeriod::get().saturated_into()))
+	}
+    fn get_price_path_with_min_confidence(source: T::Currency, target: T::Currency, min_confidence: u32) -> Option<PricePath<T::Currency, T::Amount, T::Provider>> {
+		if Self::confidence(source.clone(), target.clone())? < min_confidence {
+			return None
+		}
+		Self::get_price_path(source, target)
+	}
+    fn twap(source: T::Currency, target: T::Currency, window_blocks: u32) -> Option<T::Amount> {
+		if T::PausedHidesPaths::get() && (Paused::<T>::get() ||
+			BestPaths::<T>::get(&source, &target).map_or(false, |stored| Self::uses_paused_provider(&stored.path))) {
+			return None
+		}
+		let observations = TwapObservations::<T>::get(&source, &target);
+		let latest = observations.last()?;
+		if window_blocks == 0 {
+			return T::Amount::try_from(latest.price).ok()
+		}
+		let now = frame_system::Pallet::<T>::block_number();
+		let window_start = now.checked_sub(&window_blocks.into())?;
+		let start = observations.iter().rev().find(|observation| observation.block_number <= window_start)?;
+		let twap = latest.cumulative_at(now).wrapping_sub(start.cumulative_at(window_start)) / window_blocks as u128;
+		T::Amount::try_from(twap).ok()
+	}
+    fn convert(source: T::Currency, target: T::Currency, amount: T::Amount) -> Option<T::Amount> {
+		if source == target {
+			return Some(amount)
+		}
+		let path = Self::get_price_path(source, target)?;
+		Self::convert_with_path(&source, &target, &path, amount, Rounding::Down)
+	}
+    fn convert_with_path(source: &T::Currency, target: &T::Currency, path: &PricePath<T::Currency, T::Amount, T::Provider>, amount: T::Amount, rounding: Rounding) -> Option<T::Amount> {
+		let converted = convert_native(
+			amount.saturated_into(),
+			path.total_cost.saturated_into(),
+			T::PriceScale::get(),
+			Self::currency_decimals(source),
+			Self::currency_decimals(target),
+			rounding)?;
+		T::Amount::try_from(converted).ok()
+	}
+}
+
+impl<T: Config> BestPathHistory<T::Currency, T::Amount, T::Provider, T::BlockNumber> for Pallet<T> {
+	fn path_history(source: T::Currency, target: T::Currency) -> Vec<(T::BlockNumber, PricePath<T::Currency, T::Amount, T::Provider>)> {
+		PathHistory::<T>::get(&source, &target).into_iter().map(|StoredPricePath{path, updated_at, ..}| (updated_at, path)).collect()
 	}
 }
@@ -0,0 +1,89 @@
+//! Storage migrations, run on runtime upgrade until the onchain storage version reaches `STORAGE_VERSION`.
+
+use super::*;
+use frame_support::{ensure, traits::{GetStorageVersion, OnRuntimeUpgrade, StorageVersion}, weights::Weight};
+use sp_std::marker::PhantomData;
+#[cfg(feature = "try-runtime")]
+use frame_support::traits::OnRuntimeUpgradeHelpersExt;
+
+/// Run all migrations pending for the onchain storage version, in order
+pub fn migrate<T: Config>() -> Weight {
+	let mut weight = T::DbWeight::get().reads(1);
+	if Pallet::<T>::on_chain_storage_version() < 1 {
+		weight = weight.saturating_add(v1::MigrateToV1::<T>::on_runtime_upgrade());
+	}
+	weight
+}
+
+/// Pre-upgrade checks of all pending migrations
+#[cfg(feature = "try-runtime")]
+pub fn pre_migrate<T: Config>() -> Result<(), &'static str> {
+	if Pallet::<T>::on_chain_storage_version() < 1 {
+		v1::MigrateToV1::<T>::pre_upgrade()?;
+	}
+	Ok(())
+}
+
+/// Post-upgrade checks of all pending migrations, followed by the storage invariants check
+#[cfg(feature = "try-runtime")]
+pub fn post_migrate<T: Config>() -> Result<(), &'static str> {
+	ensure!(Pallet::<T>::on_chain_storage_version() == pallet::STORAGE_VERSION, "Storage version not migrated to STORAGE_VERSION");
+	v1::MigrateToV1::<T>::post_upgrade()?;
+	Pallet::<T>::do_try_state()
+}
+
+/// v1: `BestPaths` values changed from `PricePath` to `StoredPricePath`, `MonitoredPairs` values changed from `()` to `PairOptions`.
+/// Existing paths are deemed updated at the block of the migration, without step quotes and with a confidence of 0, ie. unknown, until their next update.
+/// Existing pairs are migrated as unidirectional.
+pub mod v1 {
+	use super::*;
+
+	pub struct MigrateToV1<T>(PhantomData<T>);
+
+	impl<T: Config> OnRuntimeUpgrade for MigrateToV1<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Pallet::<T>::on_chain_storage_version() >= 1 {
+				log::info!("Migration to v1 skipped, storage already at {:?}", Pallet::<T>::on_chain_storage_version());
+				return T::DbWeight::get().reads(1)
+			}
+
+			let updated_at = frame_system::Pallet::<T>::block_number();
+			let mut translated = 0_u64;
+			BestPaths::<T>::translate::<PricePath<T::Currency, T::Amount, T::Provider>, _>(|_, _, path| {
+				translated += 1;
+				Some(StoredPricePath{path, step_quotes: vec![], confidence: 0, updated_at})
+			});
+			MonitoredPairs::<T>::translate::<(), _>(|_, ()| {
+				translated += 1;
+				Some(PairOptions::default())
+			});
+			StorageVersion::new(1).put::<Pallet<T>>();
+			log::info!("Migrated {} best paths and monitored pairs to v1", translated);
+
+			T::DbWeight::get().reads_writes(translated + 1, translated + 1)
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn pre_upgrade() -> Result<(), &'static str> {
+			ensure!(Pallet::<T>::on_chain_storage_version() < 1, "Storage already at v1");
+			Self::set_temp_storage(BestPaths::<T>::iter_keys().count() as u32, "best_paths_count");
+			Self::set_temp_storage(MonitoredPairs::<T>::iter_keys().count() as u32, "monitored_pairs_count");
+			Ok(())
+		}
+
+		#[cfg(feature = "try-runtime")]
+		fn post_upgrade() -> Result<(), &'static str> {
+			ensure!(Pallet::<T>::on_chain_storage_version() >= 1, "Storage not migrated to v1");
+			// iterating values verifies they all decode
+			let best_paths_count = BestPaths::<T>::iter_values().count() as u32;
+			let monitored_pairs_count = MonitoredPairs::<T>::iter_values().count() as u32;
+			if let Some(pre_count) = Self::get_temp_storage::<u32>("best_paths_count") {
+				ensure!(best_paths_count == pre_count, "Best paths lost in migration to v1");
+			}
+			if let Some(pre_count) = Self::get_temp_storage::<u32>("monitored_pairs_count") {
+				ensure!(monitored_pairs_count == pre_count, "Monitored pairs lost in migration to v1");
+			}
+			Ok(())
+		}
+	}
+}
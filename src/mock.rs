@@ -3,7 +3,8 @@
 use crate as best_path;
 use crate::*;
 
-use frame_support::parameter_types;
+use codec::Encode;
+use frame_support::{parameter_types, traits::GenesisBuild, weights::Weight};
 use parking_lot::RwLock;
 use sp_core::{
     offchain::{testing, testing::PoolState, OffchainDbExt, OffchainWorkerExt, TransactionPoolExt},
@@ -12,30 +13,170 @@ use sp_core::{
 };
 use sp_keystore::{testing::KeyStore, KeystoreExt, SyncCryptoStore};
 use sp_runtime::{
+    DispatchError,
     testing::{Header, TestXt},
     traits::{BlakeTwo256, Extrinsic as ExtrinsicT, IdentifyAccount, IdentityLookup, Verify},
     RuntimeAppPublic,
 };
 use core::convert::TryFrom;
 use sp_std::vec::Vec;
-use std::sync::Arc;
+use std::{cell::RefCell, sync::Arc};
 
 pub(crate) const MOCK_PROVIDER: PriceProviderId = PriceProviderId::CRYPTOCOMPARE;
+pub(crate) const MOCK_PROVIDER2: PriceProviderId = PriceProviderId::KRAKEN;
+pub(crate) const MOCK_DEX_PROVIDER: PriceProviderId = PriceProviderId::DEX;
 pub(crate) const BTC_CURRENCY: &[u8] = b"BTC";
 pub(crate) const ETH_CURRENCY: &[u8] = b"ETH";
 pub(crate) const USDT_CURRENCY: &[u8] = b"USDT";
 pub(crate) const BOGUS_CURRENCY: &[u8] = b"__BOGUS_CURRENCY__";
+pub(crate) const MOCK_EXECUTE_WEIGHT: Weight = 1_000_000;
+pub(crate) const MOCK_ON_BEST_PATH_CHANGED_WEIGHT: Weight = 100_000;
+
+thread_local! {
+    /// Price queries made against the mock provider hub
+    pub(crate) static MOCK_QUERIES: RefCell<Vec<(PriceProviderId, Vec<u8>, Vec<u8>)>> = RefCell::new(vec![]);
+    /// Price overrides, per provider
+    pub(crate) static MOCK_PRICES: RefCell<BTreeMap<PriceProviderId, u64>> = RefCell::new(BTreeMap::new());
+    /// Offchain time taken by every price query, in millis
+    pub(crate) static MOCK_FETCH_LATENCY: RefCell<u64> = RefCell::new(0);
+    /// Mock AMM pool reserves, per pair
+    pub(crate) static MOCK_POOLS: RefCell<BTreeMap<(Vec<u8>, Vec<u8>), (u64, u64)>> = RefCell::new(BTreeMap::new());
+    /// Best path changes observed by the mock handler, as (source, target, old cost, new cost)
+    pub(crate) static MOCK_PATH_CHANGES: RefCell<Vec<(Vec<u8>, Vec<u8>, Option<u64>, Option<u64>)>> = RefCell::new(vec![]);
+}
+
+/// Mock provider hub, fails for the bogus source currency, succeeds with a fixed (or overridden) price otherwise
 pub struct MockProviderHub {}
 impl PriceProviderHub<u64, PriceProviderId> for MockProviderHub {
-    fn get_price<C: AsRef<[u8]>>(
-        _provider: &PriceProviderId,
-        _source: C,
-        _target: C,
-    ) -> Result<u64, PriceProviderErr> {
-        Ok(50_000)
+    fn get_price(provider: &PriceProviderId, query: &PriceQuery) -> Result<PriceQuote<u64>, PriceProviderErr> {
+        MOCK_QUERIES.with(|queries| queries.borrow_mut().push((provider.clone(), query.source.to_vec(), query.target.to_vec())));
+        let latency = MOCK_FETCH_LATENCY.with(|latency| *latency.borrow());
+        if latency > 0 {
+            sp_io::offchain::sleep_until(sp_io::offchain::timestamp().add(sp_runtime::offchain::Duration::from_millis(latency)));
+        }
+        if query.source == BOGUS_CURRENCY {
+            Err(http::Error::IoError.into())
+        } else {
+            let price = MOCK_PRICES.with(|prices| prices.borrow().get(provider).copied().unwrap_or(50_000));
+            Ok(PriceQuote::of_price(price))
+        }
+    }
+}
+
+/// Mock AMM, pricing pairs by the ratio of pool reserves, in native units, in either direction
+pub struct MockAmm {}
+impl traits::DexPriceSource<Vec<u8>, u64> for MockAmm {
+    fn get_pool_price(source: &Vec<u8>, target: &Vec<u8>, source_decimals: u8, target_decimals: u8, scale: u32) -> Option<PriceQuote<u64>> {
+        let (source_reserve, target_reserve) = MOCK_POOLS.with(|pools| {
+            let pools = pools.borrow();
+            pools.get(&(source.clone(), target.clone())).copied()
+                .or_else(|| pools.get(&(target.clone(), source.clone())).map(|(r1, r2)| (*r2, *r1)))
+        })?;
+        let price = (target_reserve as u128).checked_mul(10_u128.checked_pow(scale + source_decimals as u32)?)?
+            / (source_reserve as u128).checked_mul(10_u128.checked_pow(target_decimals as u32)?)?;
+        let volume = source_reserve / 10_u64.checked_pow(source_decimals as u32)?;
+        Some(PriceQuote { price: u64::try_from(price).ok()?, volume: Some(volume), bid: None, ask: None })
+    }
+}
+
+pub(crate) fn set_mock_pool(source: &[u8], target: &[u8], source_reserve: u64, target_reserve: u64) {
+    MOCK_POOLS.with(|pools| pools.borrow_mut().insert((source.to_vec(), target.to_vec()), (source_reserve, target_reserve)));
+}
+
+/// Mock best path change handler, records the changes
+pub struct MockOnBestPathChanged {}
+impl traits::OnBestPathChanged<Vec<u8>, u64, PriceProviderId> for MockOnBestPathChanged {
+    fn on_best_path_changed(source: &Vec<u8>, target: &Vec<u8>, old: Option<&PricePath<Vec<u8>, u64, PriceProviderId>>, new: Option<&PricePath<Vec<u8>, u64, PriceProviderId>>) {
+        MOCK_PATH_CHANGES.with(|changes| changes.borrow_mut().push((source.clone(), target.clone(), old.map(|p| p.total_cost), new.map(|p| p.total_cost))));
+    }
+
+    fn on_best_path_changed_weight() -> Weight {
+        MOCK_ON_BEST_PATH_CHANGED_WEIGHT
+    }
+}
+
+/// Mock DEX, swapping at fixed per mille rates, per provider and pair.
+/// Balances and rates are kept in the storage, so that failed transactions revert them.
+pub struct MockDex {}
+impl MockDex {
+    fn balance_key(who: &AccountId, currency: &[u8]) -> Vec<u8> {
+        (b"mock_dex::balance", who, currency).encode()
+    }
+
+    fn rate_key(provider: &PriceProviderId, source: &[u8], target: &[u8]) -> Vec<u8> {
+        (b"mock_dex::rate", provider, source, target).encode()
+    }
+
+    pub(crate) fn balance(who: &AccountId, currency: &[u8]) -> u64 {
+        frame_support::storage::unhashed::get_or_default(&Self::balance_key(who, currency))
+    }
+
+    pub(crate) fn set_balance(who: &AccountId, currency: &[u8], amount: u64) {
+        frame_support::storage::unhashed::put(&Self::balance_key(who, currency), &amount);
+    }
+
+    pub(crate) fn set_rate(provider: &PriceProviderId, source: &[u8], target: &[u8], per_mille: Option<u64>) {
+        match per_mille {
+            Some(per_mille) => frame_support::storage::unhashed::put(&Self::rate_key(provider, source, target), &per_mille),
+            None => frame_support::storage::unhashed::kill(&Self::rate_key(provider, source, target)),
+        }
     }
 }
 
+impl traits::PathExecutor<AccountId, Vec<u8>, u64, PriceProviderId> for MockDex {
+    fn execute_step(who: &AccountId, provider: &PriceProviderId, source: &Vec<u8>, target: &Vec<u8>, amount_in: u64) -> Result<u64, DispatchError> {
+        let per_mille: u64 = frame_support::storage::unhashed::get(&Self::rate_key(provider, source, target)).ok_or(DispatchError::Other("No pool"))?;
+        let remaining = Self::balance(who, source).checked_sub(amount_in).ok_or(DispatchError::Other("Insufficient balance"))?;
+        let amount_out = amount_in * per_mille / 1_000;
+        Self::set_balance(who, source, remaining);
+        Self::set_balance(who, target, Self::balance(who, target) + amount_out);
+        Ok(amount_out)
+    }
+
+    fn execute_weight() -> Weight {
+        MOCK_EXECUTE_WEIGHT
+    }
+}
+
+/// Benchmarks the mock provider, executing paths at the rate of 1:1
+#[cfg(feature = "runtime-benchmarks")]
+pub struct MockBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl traits::BenchmarkHelper<AccountId, Vec<u8>, u64, PriceProviderId> for MockBenchmarkHelper {
+    fn provider() -> PriceProviderId {
+        MOCK_PROVIDER
+    }
+
+    fn setup_execution(who: &AccountId, path: &PricePath<Vec<u8>, u64, PriceProviderId>, amount_in: u64) {
+        for step in path.steps.iter() {
+            MockDex::set_rate(&step.provider, &step.pair.source, &step.pair.target, Some(1_000));
+        }
+        if let Some(step) = path.steps.first() {
+            MockDex::set_balance(who, &step.pair.source, amount_in);
+        }
+    }
+}
+
+pub(crate) fn mock_path_changes() -> Vec<(Vec<u8>, Vec<u8>, Option<u64>, Option<u64>)> {
+    MOCK_PATH_CHANGES.with(|changes| changes.borrow().clone())
+}
+
+pub(crate) fn set_mock_price(provider: PriceProviderId, price: u64) {
+    MOCK_PRICES.with(|prices| prices.borrow_mut().insert(provider, price));
+}
+
+pub(crate) fn set_mock_fetch_latency(latency: u64) {
+    MOCK_FETCH_LATENCY.with(|mock_latency| *mock_latency.borrow_mut() = latency);
+}
+
+pub(crate) fn mock_fetch_attempts() -> u32 {
+    MOCK_QUERIES.with(|queries| queries.borrow().len() as u32)
+}
+
+pub(crate) fn mock_queries() -> Vec<(PriceProviderId, Vec<u8>, Vec<u8>)> {
+    MOCK_QUERIES.with(|queries| queries.borrow().clone())
+}
+
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
 type Block = frame_system::mocking::MockBlock<Test>;
 
@@ -47,7 +188,7 @@ frame_support::construct_runtime!(
         UncheckedExtrinsic = UncheckedExtrinsic,
     {
         System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
-        Fixture: best_path::{Pallet, Call, Storage, Event<T>, ValidateUnsigned},
+        Fixture: best_path::{Pallet, Call, Config<T>, Storage, Event<T>, ValidateUnsigned},
     }
 );
 
@@ -118,6 +259,33 @@ parameter_types! {
     pub const MaxTxPoolStayTime: u64 = 1;
     pub const UnsignedPriority: u64 = 1 << 20;
     pub const PriceChangeTolerance: u32 = 1;
+    pub const PriceScale: u32 = 12;
+    pub const PriceAggregation: AggregationMethod = AggregationMethod::Median;
+    pub const MaxProviderDeviation: u32 = 100_000;
+    pub const PriceFetchRetries: u32 = 1;
+    pub const PriceFetchBackoff: u64 = 100;
+    pub const ProviderFailureThreshold: u32 = 2;
+    pub const ProviderCooldown: u64 = 5;
+    pub const ReportProviderOutages: bool = true;
+    pub const MaxPriceChange: u32 = 500_000;  // 50%
+    pub const MaxWindowPriceChange: u32 = 800_000;  // 80%
+    pub const PriceChangeWindow: u64 = 10;
+    pub const SuspiciousChangeTimeout: u64 = 5;
+    pub const PausedHidesPaths: bool = true;
+    pub const MaxExecutionHops: u32 = 2;
+    pub const DexProvider: Option<PriceProviderId> = Some(MOCK_DEX_PROVIDER);
+    pub const PathHistoryDepth: u32 = 3;
+    pub const PathHistoryRetention: u64 = 20;
+    pub const MaxTwapObservations: u32 = 64;
+    pub static DeriveInversePairs: bool = false;
+    pub const InversePairHaircut: u32 = 10_000;  // 1%
+    pub static DefaultSpread: u32 = 0;
+    pub const FullConfidenceProviders: u32 = 2;
+    pub const ConfidenceDecayPeriod: u64 = 10;
+    pub static OcwTimeBudget: u64 = 0;
+    pub const MaxCachedQuoteAge: u64 = 5;
+    pub static MaxChangesPerTx: u32 = 100;
+    pub static MaxPathAge: u64 = 0;
 }
 
 impl Config for Test {
@@ -129,8 +297,40 @@ impl Config for Test {
     type MaxTxPoolStayTime = MaxTxPoolStayTime;
     type UnsignedPriority = UnsignedPriority;
     type PriceChangeTolerance = PriceChangeTolerance;
+    type PriceScale = PriceScale;
+    type PriceAggregation = PriceAggregation;
+    type MaxProviderDeviation = MaxProviderDeviation;
+    type PriceFetchRetries = PriceFetchRetries;
+    type PriceFetchBackoff = PriceFetchBackoff;
+    type ProviderFailureThreshold = ProviderFailureThreshold;
+    type ProviderCooldown = ProviderCooldown;
+    type ReportProviderOutages = ReportProviderOutages;
+    type MaxPriceChange = MaxPriceChange;
+    type MaxWindowPriceChange = MaxWindowPriceChange;
+    type PriceChangeWindow = PriceChangeWindow;
+    type SuspiciousChangeTimeout = SuspiciousChangeTimeout;
+    type PausedHidesPaths = PausedHidesPaths;
+    type MaxExecutionHops = MaxExecutionHops;
+    type DexProvider = DexProvider;
+    type PathHistoryDepth = PathHistoryDepth;
+    type PathHistoryRetention = PathHistoryRetention;
+    type MaxTwapObservations = MaxTwapObservations;
+    type DeriveInversePairs = DeriveInversePairs;
+    type InversePairHaircut = InversePairHaircut;
+    type DefaultSpread = DefaultSpread;
+    type FullConfidenceProviders = FullConfidenceProviders;
+    type ConfidenceDecayPeriod = ConfidenceDecayPeriod;
+    type OcwTimeBudget = OcwTimeBudget;
+    type MaxCachedQuoteAge = MaxCachedQuoteAge;
+    type MaxChangesPerTx = MaxChangesPerTx;
+    type MaxPathAge = MaxPathAge;
     type BestPathCalculator = best_path::prelude::noop_calculator::NoBestPathCalculator;
     type PriceProviderHub = MockProviderHub;
+    type OnBestPathChanged = (MockOnBestPathChanged, ());
+    type Executors = MockDex;
+    type DexPriceSource = MockAmm;
+    #[cfg(feature = "runtime-benchmarks")]
+    type BenchmarkHelper = MockBenchmarkHelper;
     type Currency = Vec<u8>;
     type Provider = PriceProviderId;
     type Amount = u64;
@@ -144,6 +344,15 @@ pub fn new_test_ext() -> sp_io::TestExternalities {
     t
 }
 
+/// Return test externalities with the pallet genesis config applied, after first block
+pub fn new_test_ext_with_genesis(genesis: best_path::GenesisConfig<Test>) -> sp_io::TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default().build_storage::<Test>().unwrap();
+    genesis.assimilate_storage(&mut storage).unwrap();
+    let mut t = sp_io::TestExternalities::new(storage);
+    t.execute_with(|| System::set_block_number(1));
+    t
+}
+
 pub fn new_test_ext_with_keystore() -> (
     sp_io::TestExternalities,
     testing::TestOffchainExt,
@@ -1,6 +1,7 @@
 #![cfg(test)]
 
-use crate::price_provider::crypto_compare::get_price;
+use crate::price_provider::{crypto_compare::get_price, DefaultPriceProviderHub};
+use crate::{PriceProviderErr, PriceProviderHub, PriceProviderId, PriceQuery, types::PriceQuote};
 use sp_core::offchain::{testing, OffchainWorkerExt};
 
 #[test]
@@ -42,3 +43,71 @@ fn test_get_price() {
         assert_eq!(get_price(b"BTC", b"ETH", 12).unwrap(), 12_500_000000000000);
     })
 }
+
+
+#[test]
+fn test_get_price_errors() {
+    let (offchain, state) = testing::TestOffchainExt::new();
+    let mut t = sp_io::TestExternalities::default();
+    t.register_extension(OffchainWorkerExt::new(offchain));
+
+    {
+        let mut state = state.write();
+        state.expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://min-api.cryptocompare.com/data/price?fsym=BTC&tsyms=XYZ".into(),
+            response: Some(br#"{"Response":"Error","Message":"cccagg_or_exchange market does not exist for this coin pair (BTC-XYZ)"}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+
+        state.expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://min-api.cryptocompare.com/data/price?fsym=BTC&tsyms=USD".into(),
+            response: Some(br#"{"USD": 0}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+
+        state.expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://min-api.cryptocompare.com/data/price?fsym=ETH&tsyms=USD".into(),
+            response: Some(br#"<html>Service Unavailable</html>"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+    }
+
+    t.execute_with(|| {
+        assert_eq!(get_price(b"BTC", b"XYZ", 12), Err(PriceProviderErr::UnsupportedPairErr));
+        assert_eq!(get_price(b"BTC", b"USD", 12), Err(PriceProviderErr::InvalidPriceErr));
+        assert_eq!(get_price(b"ETH", b"USD", 12), Err(PriceProviderErr::ParseErr));
+    })
+}
+
+#[test]
+fn test_default_hub_prices_whole_units() {
+    let (offchain, state) = testing::TestOffchainExt::new();
+    let mut t = sp_io::TestExternalities::default();
+    t.register_extension(OffchainWorkerExt::new(offchain));
+
+    for (source, response) in [("BTC", br#"{"USDT": 50000.5}"#.to_vec()), ("SHIB", br#"{"USDT": 0.00001}"#.to_vec())] {
+        state.write().expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: format!("https://min-api.cryptocompare.com/data/price?fsym={}&tsyms=USDT", source),
+            response: Some(response),
+            sent: true,
+            ..Default::default()
+        });
+    }
+
+    t.execute_with(|| {
+        // prices are of whole units, regardless of the currency decimals, so that prices below a native unit are retained
+        let query = PriceQuery { source: b"BTC", target: b"USDT", scale: 12 };
+        assert_eq!(DefaultPriceProviderHub::get_price(&PriceProviderId::CRYPTOCOMPARE, &query), Ok(PriceQuote::of_price(50_000_500000000000)));
+        let query = PriceQuery { source: b"SHIB", ..query };
+        assert_eq!(DefaultPriceProviderHub::get_price(&PriceProviderId::CRYPTOCOMPARE, &query), Ok(PriceQuote::of_price(10_000_000)));
+        // onchain pools aren't fetched by the hub
+        assert_eq!(DefaultPriceProviderHub::get_price(&PriceProviderId::DEX, &query), Err(PriceProviderErr::UnsupportedPairErr));
+    })
+}
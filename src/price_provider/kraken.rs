@@ -0,0 +1,77 @@
+use lite_json::json::JsonValue;
+use scale_info::prelude::string::String;
+use sp_std::{vec::Vec, str};
+use crate::utils::{chars_eq, concat, decimal_to_price, json_field, parse_decimal};
+use crate::{PriceProviderErr, types::PriceQuote};
+use super::http_get;
+
+/// Fetch the last trade price, best bid/ask and 24h volume from Kraken ticker, note Kraken's tickers differ from other providers, eg. XBT for BTC.
+pub fn get_quote(source: &[u8], target: &[u8], scale: u32) -> Result<PriceQuote<u128>, PriceProviderErr> {
+	let body = http_get(&concat(&[b"https://api.kraken.com/0/public/Ticker?pair=", source, target]))?;
+	let body_str = str::from_utf8(&body).map_err(|_| PriceProviderErr::ParseErr)?;
+
+	let quote = parse_ticker_quote(body_str, scale).map_err(|e| {
+		log::warn!("Unable to extract price from the response: {:?} due to {:?}", body_str, e);
+		e
+	})?;
+
+	log::info!("Got price: {}", quote.price as f64 / 10_u128.saturating_pow(scale) as f64);
+
+	Ok(quote)
+}
+
+/// Parse the last trade price, best bid/ask and 24h volume from the ticker response, eg. `{"error": [], "result": {"XXBTZUSD": {"a": ["50001.0", "1", "1.0"], "b": ["49999.0", "2", "2.0"], "c": ["50000.1", "0.1"], "v": ["10.5", "25.3"], ...}}}`.
+///
+/// Note, the result is keyed by Kraken's internal pair name, hence the first (and only) result is taken.
+pub fn parse_ticker_quote(ticker_str: &str, scale: u32) -> Result<PriceQuote<u128>, PriceProviderErr> {
+	let obj = match lite_json::parse_json(ticker_str).map_err(|_| PriceProviderErr::ParseErr)? {
+		JsonValue::Object(obj) => obj,
+		_ => return Err(PriceProviderErr::ParseErr),
+	};
+
+	if let Some(JsonValue::Array(errors)) = json_field(&obj, b"error") {
+		match errors.first() {
+			Some(JsonValue::String(e)) if starts_with(e, b"EQuery:Unknown asset pair") => return Err(PriceProviderErr::UnsupportedPairErr),
+			Some(JsonValue::String(e)) if starts_with(e, b"EAPI:Rate limit exceeded") => return Err(PriceProviderErr::RateLimitedErr),
+			Some(_) => return Err(PriceProviderErr::ParseErr),
+			None => (),
+		}
+	}
+
+	let ticker = match json_field(&obj, b"result") {
+		Some(JsonValue::Object(result)) => result.first().map(|(_, ticker)| ticker),
+		_ => None,
+	};
+	let ticker = match ticker {
+		Some(JsonValue::Object(ticker)) => ticker,
+		_ => return Err(PriceProviderErr::ParseErr),
+	};
+	let price = match array_item(ticker, b"c", 0) {
+		Some(price) => decimal_to_price(parse_decimal(&price, scale))?,
+		None => return Err(PriceProviderErr::ParseErr),
+	};
+	let volume = array_item(ticker, b"v", 1).and_then(|volume| parse_decimal(&volume, 0).ok());
+	let bid = array_item(ticker, b"b", 0).and_then(|bid| decimal_to_price(parse_decimal(&bid, scale)).ok());
+	let ask = array_item(ticker, b"a", 0).and_then(|ask| decimal_to_price(parse_decimal(&ask, scale)).ok());
+	// crossed book is deemed invalid, falling back to the last trade price
+	let (bid, ask) = match (bid, ask) {
+		(Some(bid), Some(ask)) if bid > ask => (None, None),
+		sides => sides,
+	};
+	Ok(PriceQuote { price, volume, bid, ask })
+}
+
+/// Lookup string item of the array field, as per Kraken's ticker format
+fn array_item(ticker: &[(Vec<char>, JsonValue)], key: &[u8], index: usize) -> Option<String> {
+	match json_field(ticker, key) {
+		Some(JsonValue::Array(items)) => match items.get(index) {
+			Some(JsonValue::String(item)) => Some(item.iter().collect()),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+fn starts_with(chars: &[char], prefix: &[u8]) -> bool {
+	chars.len() >= prefix.len() && chars_eq(&chars[..prefix.len()], prefix)
+}
@@ -0,0 +1,50 @@
+#![cfg(test)]
+
+use crate::price_provider::kraken::{get_quote, parse_ticker_quote};
+use crate::{PriceProviderErr, types::PriceQuote};
+use sp_core::offchain::{testing, OffchainWorkerExt};
+
+#[test]
+fn test_get_price() {
+    let (offchain, state) = testing::TestOffchainExt::new();
+    let mut t = sp_io::TestExternalities::default();
+    t.register_extension(OffchainWorkerExt::new(offchain));
+
+    {
+        let mut state = state.write();
+        state.expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://api.kraken.com/0/public/Ticker?pair=XBTUSD".into(),
+            response: Some(br#"{"error":[],"result":{"XXBTZUSD":{"a":["50001.00000","1","1.000"],"b":["49999.00000","2","2.000"],"c":["50000.10000","0.00100000"],"v":["1000.5","2500.75"],"o":"49000.00000"}}}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+
+        state.expect_request(testing::PendingRequest {
+            method: "GET".into(),
+            uri: "https://api.kraken.com/0/public/Ticker?pair=XBTXYZ".into(),
+            response: Some(br#"{"error":["EQuery:Unknown asset pair"]}"#.to_vec()),
+            sent: true,
+            ..Default::default()
+        });
+    }
+
+    t.execute_with(|| {
+        assert_eq!(get_quote(b"XBT", b"USD", 12), Ok(PriceQuote { price: 50_000_100000000000, volume: Some(2500), bid: Some(49_999_000000000000), ask: Some(50_001_000000000000) }));
+        assert_eq!(get_quote(b"XBT", b"XYZ", 12), Err(PriceProviderErr::UnsupportedPairErr));
+    })
+}
+
+#[test]
+fn test_parse_ticker_quote() {
+    assert_eq!(parse_ticker_quote(r#"{"error":[],"result":{"XETHZUSD":{"c":["4000.5","1"]}}}"#, 2), Ok(PriceQuote::of_price(400_050)));
+    assert_eq!(parse_ticker_quote(r#"{"error":[],"result":{"XETHZUSD":{"a":["4001","1","1"],"b":["3999.5","1","1"],"c":["4000.5","1"]}}}"#, 2), Ok(PriceQuote { price: 400_050, volume: None, bid: Some(399_950), ask: Some(400_100) }));
+    // crossed or unparsable bid/ask are ignored
+    assert_eq!(parse_ticker_quote(r#"{"error":[],"result":{"XETHZUSD":{"a":["3999","1","1"],"b":["4001","1","1"],"c":["4000.5","1"]}}}"#, 2), Ok(PriceQuote::of_price(400_050)));
+    assert_eq!(parse_ticker_quote(r#"{"error":[],"result":{"XETHZUSD":{"a":["abc","1","1"],"b":["3999.5","1","1"],"c":["4000.5","1"]}}}"#, 2), Ok(PriceQuote { price: 400_050, volume: None, bid: Some(399_950), ask: None }));
+    assert_eq!(parse_ticker_quote(r#"{"error":["EAPI:Rate limit exceeded"]}"#, 12), Err(PriceProviderErr::RateLimitedErr));
+    assert_eq!(parse_ticker_quote(r#"{"error":["EGeneral:Internal error"]}"#, 12), Err(PriceProviderErr::ParseErr));
+    assert_eq!(parse_ticker_quote(r#"{"error":[],"result":{"XETHZUSD":{"c":["0.0","1"]}}}"#, 12), Err(PriceProviderErr::InvalidPriceErr));
+    assert_eq!(parse_ticker_quote(r#"{"error":[],"result":{}}"#, 12), Err(PriceProviderErr::ParseErr));
+    assert_eq!(parse_ticker_quote(r#"[]"#, 12), Err(PriceProviderErr::ParseErr));
+}
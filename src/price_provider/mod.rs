@@ -1,16 +1,55 @@
 pub mod crypto_compare;
 mod crypto_compare_tests;
-use crate::{PriceProviderId, PriceProviderHub, PriceProviderErr};
-use sp_std::convert::AsRef;
-
-const SCALE: u32 = 12;
+pub mod kraken;
+mod kraken_tests;
+use crate::{PriceProviderId, PriceProviderHub, PriceProviderErr, PriceQuery, types::PriceQuote};
+use sp_std::{vec::Vec, str};
+use sp_runtime::offchain::{http, Duration};
 
 /// Default implementation of price provider, aggregates functionality of fetching per different providers.
+///
+/// Prices are of whole currency units, currency decimals are only applied upon conversion of native amounts.
 pub struct DefaultPriceProviderHub {}
 impl PriceProviderHub<u128, PriceProviderId> for DefaultPriceProviderHub {
-	fn get_price<C: AsRef<[u8]>>(oracle_id: &PriceProviderId, source: C, target: C) -> Result<u128, PriceProviderErr> {
-		match oracle_id {
-			PriceProviderId::CRYPTOCOMPARE => crypto_compare::get_price(source.as_ref(), target.as_ref(), SCALE),
+	fn get_price(oracle_id: &PriceProviderId, query: &PriceQuery) -> Result<PriceQuote<u128>, PriceProviderErr> {
+		let quote = match oracle_id {
+			PriceProviderId::CRYPTOCOMPARE => crypto_compare::get_price(query.source, query.target, query.scale).map(PriceQuote::of_price),
+			PriceProviderId::KRAKEN => kraken::get_quote(query.source, query.target, query.scale),
+			PriceProviderId::DEX => Err(PriceProviderErr::UnsupportedPairErr),  // onchain pools are read via `DexPriceSource`
+		}?;
+		let non_zero = |price: u128| Some(price).filter(|price| *price > 0);
+		match quote.price {
+			0 => Err(PriceProviderErr::InvalidPriceErr),
+			_ => Ok(PriceQuote { bid: quote.bid.and_then(non_zero), ask: quote.ask.and_then(non_zero), ..quote }),
 		}
 	}
 }
+
+/// Issue HTTP GET request, returning the UTF8 body of a successful response
+pub(crate) fn http_get(url_bin: &[u8]) -> Result<Vec<u8>, PriceProviderErr> {
+	let deadline = sp_io::offchain::timestamp().add(Duration::from_millis(2_000));  // expiry = 2s
+	let url = str::from_utf8(url_bin).map_err(|err| {
+		log::error!("url utf8 parsing error: {:?}", err);
+		PriceProviderErr::UnsupportedPairErr
+	})?;
+	let request = http::Request::get(url);
+	let pending = request.deadline(deadline).send().map_err(|_| http::Error::IoError)?;
+	let response = pending.try_wait(deadline).map_err(|_| http::Error::DeadlineReached)??;
+	match response.code {
+		200 => (),
+		429 => {
+			log::warn!("Rate limited");
+			return Err(PriceProviderErr::RateLimitedErr)
+		},
+		code => {
+			log::warn!("Unexpected status code: {}", code);
+			return Err(PriceProviderErr::HttpStatusErr(code))
+		},
+	}
+	let body = response.body().collect::<Vec<u8>>();
+	if str::from_utf8(&body).is_err() {
+		log::warn!("No UTF8 body");
+		return Err(PriceProviderErr::ParseErr)
+	}
+	Ok(body)
+}
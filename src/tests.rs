@@ -2,28 +2,29 @@
 
 use crate::*;
 
-use codec::Decode;
-use frame_support::{assert_ok, assert_noop};
+use codec::{Decode, Encode};
+use frame_support::{assert_ok, assert_noop, dispatch::{DispatchResultWithPostInfo, GetDispatchInfo}, traits::{GetStorageVersion, OnInitialize, OnRuntimeUpgrade, StorageVersion}};
 use sp_std::{collections::btree_set::BTreeSet, vec::Vec};
-use sp_runtime::traits::BadOrigin;
-use crate::mock::{Test, BTC_CURRENCY, ETH_CURRENCY, MOCK_PROVIDER, BOGUS_CURRENCY, USDT_CURRENCY, Call, Origin, Extrinsic, Event, Fixture, new_test_ext, last_event, new_test_ext_with_keystore};
+use sp_runtime::{traits::BadOrigin, transaction_validity::TransactionSource, DispatchError};
+use frame_support::unsigned::ValidateUnsigned;
+use crate::mock::{Test, BTC_CURRENCY, ETH_CURRENCY, MOCK_PROVIDER, MOCK_PROVIDER2, MOCK_DEX_PROVIDER, MOCK_EXECUTE_WEIGHT, MOCK_ON_BEST_PATH_CHANGED_WEIGHT, BOGUS_CURRENCY, USDT_CURRENCY, Call, Origin, Extrinsic, Event, Fixture, MockDex, System, new_test_ext, new_test_ext_with_genesis, last_event, new_test_ext_with_keystore, mock_fetch_attempts, mock_path_changes, mock_queries, set_mock_fetch_latency, set_mock_pool, set_mock_price};
 
 #[test]
 fn test_submit_monitored_pairs_ok() {
 	new_test_ext().execute_with(|| {
 		// validate deletion of non existent entry - should succeed, but expect no storage change/event
 		assert_ok!(Fixture::submit_monitored_pairs(Origin::root(), vec![
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: BOGUS_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Del},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: BOGUS_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false},
 		]));
 		assert_eq!(last_event(), None);
 		assert_eq!(0, MonitoredPairs::<Test>::iter_keys().count());
 
 		// validate additions
 		assert_ok!(Fixture::submit_monitored_pairs(Origin::root(), vec![
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add},
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Del},
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Add},  // deduped, replaces the Del above
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false},  // deduped, replaces the Del above
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false},
 		]));
 		assert_eq!(vec![
 				ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER},
@@ -40,11 +41,11 @@ fn test_submit_monitored_pairs_ok() {
 
 		// validate deletions
 		assert_ok!(Fixture::submit_monitored_pairs(Origin::root(), vec![
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Del},
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},   provider: MOCK_PROVIDER}, operation: Operation::Add},
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},   provider: MOCK_PROVIDER}, operation: Operation::Del},  // deduped, replaces the Add above
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},   provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},   provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false},  // deduped, replaces the Add above
 			// Note: ETH - USDT still remains
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: BOGUS_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Del},  // expect it skipped in the event
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: BOGUS_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false},  // expect it skipped in the event
 		]));
 		assert_eq!(vec![
 				ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER},
@@ -58,9 +59,9 @@ fn test_submit_monitored_pairs_ok() {
 
 		// validate mixture of additions and deletions
 		assert_ok!(Fixture::submit_monitored_pairs(Origin::root(), vec![
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(),   target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Del},
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BOGUS_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Del}, // expect it skipped in the event
-			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: USDT_CURRENCY.to_vec(),  target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Add},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(),   target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false},
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BOGUS_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Del, bidirectional: false}, // expect it skipped in the event
+			ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: USDT_CURRENCY.to_vec(),  target: ETH_CURRENCY.to_vec()},  provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false},
 		]));
 		assert_eq!(vec![
 				ProviderPair{pair: Pair{source: USDT_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()}, provider: MOCK_PROVIDER},
@@ -79,12 +80,12 @@ fn test_submit_monitored_pairs_errors() {
 	new_test_ext().execute_with(|| {
 		// validate incorrect origins
 		assert_noop!(
-			Fixture::submit_monitored_pairs(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), vec![ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add}]),
+			Fixture::submit_monitored_pairs(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), vec![ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false}]),
 			BadOrigin);
 		assert_eq!(0, MonitoredPairs::<Test>::iter_keys().count());
 
 		assert_noop!(
-			Fixture::submit_monitored_pairs(Origin::none(), vec![ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add}]),
+			Fixture::submit_monitored_pairs(Origin::none(), vec![ProviderPairOperation{provider_pair: ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, operation: Operation::Add, bidirectional: false}]),
 			BadOrigin);
 		assert_eq!(0, MonitoredPairs::<Test>::iter_keys().count());
 	});
@@ -97,6 +98,9 @@ fn test_ocw_submit_best_paths_changes() {
 		nonce: 0,
 		block_number: 1,
 		changes: vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost: 50000, steps: vec![]}))],
+		step_quotes: vec![],
+		confidences: vec![],
+		provider_outages: vec![],
 		public: <Test as SigningTypes>::Public::from(*public_key),
 	};
 	let payload2 = payload.clone();
@@ -110,6 +114,13 @@ fn test_ocw_submit_best_paths_changes() {
 				<Test as Config>::Amount,
 				<Test as Config>::Provider,
 			> as SignedPayload<Test>>::sign::<crypto::AuthId>(&payload).unwrap();
+
+		// best path change handlers are charged per change on top of the pallet weight
+		let mut payload3 = payload.clone();
+		payload3.changes.push((ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None));
+		let call = Call::Fixture(crate::Call::ocw_submit_best_paths_changes{best_path_change_payload: payload3, signature: signature.clone()});
+		assert_eq!(<() as WeightInfo>::ocw_submit_best_paths_changes(2, 0) + 2 * MOCK_ON_BEST_PATH_CHANGED_WEIGHT, call.get_dispatch_info().weight);
+
 		assert_ok!(Fixture::ocw_submit_best_paths_changes(Origin::none(), payload.clone(), signature.clone()));
 		assert_noop!(Fixture::ocw_submit_best_paths_changes(Origin::none(), payload, signature), Error::<Test>::StaleUnsignedTxError);
 	});
@@ -136,6 +147,9 @@ fn test_fetch_prices_and_update_best_paths() {
 		nonce: 0,
 		block_number: 1,
 		changes: vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost: 50000, steps: vec![]}))],
+		step_quotes: vec![],
+		confidences: vec![],  // stepless path of unknown confidence, submitted without one
+		provider_outages: vec![],
 		public: <Test as SigningTypes>::Public::from(*public_key),
 	};
 
@@ -143,7 +157,7 @@ fn test_fetch_prices_and_update_best_paths() {
 	t.execute_with(|| {
 		MonitoredPairs::<Test>::insert(
 			ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, 
-			());
+			PairOptions::default());
 
 		assert!(Fixture::fetch_prices_and_update_best_paths(1).is_ok());
 		let tx = pool_state.write().transactions.pop().unwrap();
@@ -171,6 +185,137 @@ fn test_fetch_prices_and_update_best_paths() {
 	});
 }
 
+#[test]
+fn test_fetch_prices_and_update_best_paths_in_chunks() {
+	let (t, _, pool_state, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, PairOptions::default());
+		crate::mock::MaxChangesPerTx::set(1);
+
+		// a submission per change, sequenced by nonces
+		assert!(Fixture::fetch_prices_and_update_best_paths(1).is_ok());
+		let payloads = pool_state.read().transactions.iter()
+			.map(|tx| match Extrinsic::decode(&mut &**tx).unwrap().call {
+				Call::Fixture(crate::Call::ocw_submit_best_paths_changes { best_path_change_payload, .. }) => best_path_change_payload,
+				call => panic!("Unexpected call: {:?}", call),
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(vec![0, 1], payloads.iter().map(|payload| payload.nonce).collect::<Vec<_>>());
+		assert!(payloads.iter().all(|payload| payload.changes.len() == 1));
+		assert_eq!(
+			vec![BTC_CURRENCY.to_vec(), ETH_CURRENCY.to_vec()].into_iter().collect::<BTreeSet<_>>(),
+			payloads.iter().map(|payload| payload.changes[0].0.clone()).collect::<BTreeSet<_>>());
+		crate::mock::MaxChangesPerTx::set(100);
+	});
+}
+
+#[test]
+fn test_validate_unsigned_nonce_sequencing() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		WhitelistedOffchainAuthorities::<Test>::insert(*public_key, ());
+		let change = |i: u32| (format!("SRC{}", i).into_bytes(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost: 100, steps: vec![]}));
+		let tag = |nonce: u64| ("BestPathWorker", (TX_TAG, nonce)).encode();
+
+		// current nonce, no predecessor required
+		let valid = Fixture::validate_unsigned(TransactionSource::Local, &signed_call(public_key, 0, vec![change(0)])).unwrap();
+		assert_eq!(vec![tag(0)], valid.provides);
+		assert!(valid.requires.is_empty());
+
+		// subsequent chunk awaits its predecessor
+		let valid = Fixture::validate_unsigned(TransactionSource::Local, &signed_call(public_key, 2, vec![change(2)])).unwrap();
+		assert_eq!(vec![tag(2)], valid.provides);
+		assert_eq!(vec![tag(1)], valid.requires);
+
+		// oversized chunk
+		assert_eq!(
+			Err(InvalidTransaction::ExhaustsResources.into()),
+			Fixture::validate_unsigned(TransactionSource::Local, &signed_call(public_key, 0, (0..=100).map(change).collect())));
+
+		// already applied nonce
+		UnsignedTxNonce::<Test>::set(3);
+		assert_eq!(
+			Err(InvalidTransaction::Stale.into()),
+			Fixture::validate_unsigned(TransactionSource::Local, &signed_call(public_key, 2, vec![change(2)])));
+	});
+}
+
+#[test]
+fn test_submit_chunked_changes() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		let changes = (0..10_000)
+			.map(|i| (format!("SRC{}", i).into_bytes(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost: 100 + i, steps: vec![]})))
+			.collect::<Vec<_>>();
+		let chunks = Fixture::chunk_changes(changes.clone(), &BTreeMap::new(), 100);
+		assert_eq!(100, chunks.len());
+
+		// oversized chunk rejected as a whole
+		assert_noop!(submit_changes(public_key, 0, changes.clone()), Error::<Test>::TooManyChangesError);
+
+		// chunks applied in the nonce order, each on its own
+		for (nonce, (chunk, step_quotes, confidences)) in chunks.into_iter().enumerate().take(60) {
+			assert_ok!(submit_changes_with_quotes(public_key, nonce as u64, chunk, step_quotes, confidences));
+		}
+		assert_eq!(60, UnsignedTxNonce::<Test>::get());
+		assert_eq!(6_000, BestPaths::<Test>::iter_keys().count());
+
+		// chunk lost, eg. dropped from the pool, leaves the preceding ones applied, and the subsequent ones rejected
+		assert_noop!(submit_changes(public_key, 61, changes[6_100..6_200].to_vec()), Error::<Test>::StaleUnsignedTxError);
+		assert_eq!(6_000, BestPaths::<Test>::iter_keys().count());
+
+		// remainder resubmitted by the next OCW run
+		let remainder = Fixture::chunk_changes(changes[6_000..].to_vec(), &BTreeMap::new(), 100);
+		assert_eq!(40, remainder.len());
+		for (i, (chunk, step_quotes, confidences)) in remainder.into_iter().enumerate() {
+			assert_ok!(submit_changes_with_quotes(public_key, 60 + i as u64, chunk, step_quotes, confidences));
+		}
+		assert_eq!(100, UnsignedTxNonce::<Test>::get());
+		assert_eq!(10_000, BestPaths::<Test>::iter_keys().count());
+		assert_eq!(Some(10_099), BestPaths::<Test>::get(b"SRC9999".to_vec(), USDT_CURRENCY.to_vec()).map(|p| p.path.total_cost));
+	});
+}
+
+#[test]
+fn test_chunk_changes() {
+	let step = |i: u64| PathStep{pair: Pair{source: format!("SRC{}", i).into_bytes(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER, cost: 100 + i};
+	let pp = |step: &PathStep<Vec<u8>, u64, PriceProviderId>| ProviderPair{pair: step.pair.clone(), provider: MOCK_PROVIDER};
+	let changes = (0..10_000)
+		.map(|i| (format!("SRC{}", i).into_bytes(), USDT_CURRENCY.to_vec(), match i % 3 {
+			0 => None,
+			1 => Some(PricePath{total_cost: 100 + i, steps: vec![step(i)]}),
+			_ => Some(PricePath{total_cost: 100 + i, steps: vec![]}),
+		}))
+		.collect::<Vec<_>>();
+	let edge_quotes = changes.iter()
+		.flat_map(|(_, _, path)| path.iter().flat_map(|path| path.steps.iter()))
+		.map(|step| (pp(step), (StepQuote{bid: step.cost, ask: step.cost + 1}, 900_000)))
+		.collect::<BTreeMap<_, _>>();
+
+	let chunks = Fixture::chunk_changes(changes.clone(), &edge_quotes, 300);
+	assert_eq!(34, chunks.len());
+	assert!(chunks[..33].iter().all(|(chunk, _, _)| chunk.len() == 300));
+	assert_eq!(100, chunks[33].0.len());
+	// order preserved
+	assert_eq!(changes, chunks.iter().flat_map(|(chunk, _, _)| chunk.iter().cloned()).collect::<Vec<_>>());
+	// quotes and confidences of the chunk's own paths, those without steps of unknown confidence
+	for (chunk, step_quotes, confidences) in chunks.iter() {
+		let stepped = chunk.iter()
+			.filter_map(|(_, _, path)| path.as_ref().and_then(|path| path.steps.first()))
+			.collect::<Vec<_>>();
+		assert_eq!(
+			stepped.iter().map(|step| (pp(step), StepQuote{bid: step.cost, ask: step.cost + 1})).collect::<BTreeSet<_>>(),
+			step_quotes.iter().cloned().collect::<BTreeSet<_>>());
+		assert_eq!(
+			stepped.iter().map(|step| (step.pair.source.clone(), USDT_CURRENCY.to_vec(), 900_000)).collect::<BTreeSet<_>>(),
+			confidences.iter().cloned().collect::<BTreeSet<_>>());
+	}
+	assert_eq!(3_333, chunks.iter().map(|(_, _, confidences)| confidences.len()).sum::<usize>());
+}
+
 #[test]
 fn test_should_trigger_offchain() {
 	let (t, _, _, _) = &mut new_test_ext_with_keystore();
@@ -181,3 +326,1210 @@ fn test_should_trigger_offchain() {
 		assert!(Fixture::should_trigger_offchain(11));
 	});
 }
+
+#[test]
+fn test_fetch_prices_provider_outage() {
+	let (t, _, pool_state, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		MonitoredPairs::<Test>::insert(
+			ProviderPair{pair: Pair{source: BOGUS_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER},
+			PairOptions::default());
+
+		// first failure, retried once, below failure threshold
+		assert!(Fixture::fetch_prices_and_update_best_paths(1).is_ok());
+		assert_eq!(2, mock_fetch_attempts());
+		assert!(pool_state.read().transactions.is_empty());
+		assert_eq!(ProviderHealth{consecutive_failures: 1, skip_until: None}, Fixture::provider_health(&MOCK_PROVIDER));
+		assert_eq!(Some(&1), Fixture::fetch_failure_stats().get(b"transport".as_ref()));
+
+		// second failure, breaches failure threshold, outage reported
+		assert!(Fixture::fetch_prices_and_update_best_paths(2).is_ok());
+		assert_eq!(4, mock_fetch_attempts());
+		assert_eq!(ProviderHealth{consecutive_failures: 2, skip_until: Some(7)}, Fixture::provider_health(&MOCK_PROVIDER));
+		let tx = pool_state.write().transactions.pop().unwrap();
+		let decoded_tx = Extrinsic::decode(&mut &*tx).unwrap();
+		if let Call::Fixture(crate::Call::ocw_submit_best_paths_changes { best_path_change_payload: body, .. }) = decoded_tx.call {
+			assert_eq!(Vec::<(Vec<u8>, Vec<u8>, Option<PricePath<Vec<u8>, u64, PriceProviderId>>)>::new(), body.changes);
+			assert_eq!(vec![MOCK_PROVIDER], body.provider_outages);
+		} else {
+			panic!("Unexpected call: {:?}", decoded_tx.call);
+		}
+
+		// provider skipped within the cooldown
+		assert!(Fixture::fetch_prices_and_update_best_paths(6).is_ok());
+		assert_eq!(4, mock_fetch_attempts());
+
+		// provider retried after the cooldown, failure extends the cooldown without another outage report
+		assert!(Fixture::fetch_prices_and_update_best_paths(7).is_ok());
+		assert_eq!(6, mock_fetch_attempts());
+		assert_eq!(ProviderHealth{consecutive_failures: 3, skip_until: Some(12)}, Fixture::provider_health(&MOCK_PROVIDER));
+		assert_eq!(Some(&3), Fixture::fetch_failure_stats().get(b"transport".as_ref()));
+		assert!(pool_state.read().transactions.is_empty());
+	});
+}
+
+#[test]
+fn test_fetch_prices_time_budget() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2}, PairOptions::default());
+		crate::mock::OcwTimeBudget::set(150);
+		set_mock_fetch_latency(100);
+
+		// budget exhausted after 2 fetches, the remaining pair is yet to be fetched
+		let (fetched_quotes, _) = Fixture::fetch_prices(1);
+		assert_eq!(2, fetched_quotes.len());
+		let first_round = mock_queries();
+		assert_eq!(2, first_round.len());
+
+		// remaining pair resumed first, the least recently fetched pair priced from its latest fetch
+		let (fetched_quotes, _) = Fixture::fetch_prices(2);
+		assert_eq!(3, fetched_quotes.len());
+		let second_round = mock_queries()[2..].to_vec();
+		assert_eq!(2, second_round.len());
+		assert!(!first_round.contains(&second_round[0]));
+		assert!(first_round.contains(&second_round[1]));
+
+		// no budget, all fetched
+		crate::mock::OcwTimeBudget::set(0);
+		let (fetched_quotes, _) = Fixture::fetch_prices(3);
+		assert_eq!(3, fetched_quotes.len());
+		assert_eq!(7, mock_fetch_attempts());
+
+		// quotes cached beyond MaxCachedQuoteAge aren't used, the discarded quote is fetched next
+		crate::mock::OcwTimeBudget::set(150);
+		let (fetched_quotes, _) = Fixture::fetch_prices(10);
+		assert_eq!(2, fetched_quotes.len());
+		let (fetched_quotes, _) = Fixture::fetch_prices(11);
+		assert_eq!(3, fetched_quotes.len());
+
+		// cached quotes of paused providers aren't used either
+		crate::mock::OcwTimeBudget::set(50);
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER2)));
+		let (fetched_quotes, _) = Fixture::fetch_prices(12);
+		assert_eq!(vec![MOCK_PROVIDER], fetched_quotes.into_iter().map(|(pp, _)| pp.provider).collect::<Vec<_>>());
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER2)));
+
+		// pool reads don't count against the budget, nor are skipped once it's exhausted
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()}, provider: MOCK_DEX_PROVIDER}, PairOptions::default());
+		set_mock_pool(BTC_CURRENCY, ETH_CURRENCY, 10, 200);
+		crate::mock::OcwTimeBudget::set(0);
+		Fixture::fetch_prices(13);
+		// the pool pair attempted last, with the offchain providers paused
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER)));
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER2)));
+		Fixture::fetch_prices(14);
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER)));
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER2)));
+		// all cached quotes stale, a single HTTP query exhausts the budget
+		crate::mock::OcwTimeBudget::set(50);
+		let (fetched_quotes, _) = Fixture::fetch_prices(20);
+		assert_eq!(2, fetched_quotes.len());
+		assert_eq!(MOCK_DEX_PROVIDER, fetched_quotes[1].0.provider);
+		set_mock_fetch_latency(0);
+	});
+}
+
+#[test]
+#[should_panic(expected = "MaxChangesPerTx must be positive")]
+fn test_integrity_test_zero_max_changes_per_tx() {
+	crate::mock::MaxChangesPerTx::set(0);
+	<Fixture as frame_support::traits::Hooks<u64>>::integrity_test();
+}
+
+#[test]
+fn test_submit_currency_metadata() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		let btc_meta = CurrencyMeta{symbol: BTC_CURRENCY.to_vec(), decimals: 8, display_name: b"Bitcoin".to_vec()};
+		let usdt_meta = CurrencyMeta{symbol: USDT_CURRENCY.to_vec(), decimals: 6, display_name: b"Tether".to_vec()};
+
+		assert_noop!(Fixture::submit_currency_metadata(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), BTC_CURRENCY.to_vec(), Some(btc_meta.clone())), BadOrigin);
+		assert_ok!(Fixture::submit_currency_metadata(Origin::root(), BTC_CURRENCY.to_vec(), Some(btc_meta.clone())));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::CurrencyMetadataSubmitted(BTC_CURRENCY.to_vec(), Operation::Add))));
+		assert_ok!(Fixture::submit_currency_metadata(Origin::root(), USDT_CURRENCY.to_vec(), Some(usdt_meta)));
+		assert_eq!(Some(btc_meta.clone()), CurrencyMetadata::<Test>::get(BTC_CURRENCY.to_vec()));
+		assert_eq!(8, Fixture::currency_decimals(&BTC_CURRENCY.to_vec()));
+		assert_eq!(0, Fixture::currency_decimals(&ETH_CURRENCY.to_vec()));
+		assert_eq!("Bitcoin", BTC_CURRENCY.to_vec().to_display_str(Some(&btc_meta)));
+		assert_eq!("ETH", ETH_CURRENCY.to_vec().to_display_str(None));
+
+		// removal
+		assert_ok!(Fixture::submit_currency_metadata(Origin::root(), BTC_CURRENCY.to_vec(), None));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::CurrencyMetadataSubmitted(BTC_CURRENCY.to_vec(), Operation::Del))));
+		assert_eq!(None, CurrencyMetadata::<Test>::get(BTC_CURRENCY.to_vec()));
+	});
+}
+
+#[test]
+fn test_submit_provider_tickers() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		let tickers = vec![
+			(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(),  Some(b"XBT".to_vec())),
+			(MOCK_PROVIDER2, USDT_CURRENCY.to_vec(), Some(b"USD".to_vec())),
+		];
+		assert_noop!(Fixture::submit_provider_tickers(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), tickers.clone()), BadOrigin);
+		assert_ok!(Fixture::submit_provider_tickers(Origin::root(), tickers.clone()));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::ProviderTickersSubmitted(tickers))));
+
+		// mapping applies to the provider only, and is reversible
+		assert_eq!(b"XBT".to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER2, &BTC_CURRENCY.to_vec()));
+		assert_eq!(BTC_CURRENCY.to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER, &BTC_CURRENCY.to_vec()));
+		assert_eq!(Some(BTC_CURRENCY.to_vec()), ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"XBT".to_vec()));
+		assert_eq!(Some(USDT_CURRENCY.to_vec()), ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"USD".to_vec()));
+		assert_eq!(None, ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER, b"XBT".to_vec()));
+
+		// same logical pair is fetched from both providers, with their respective tickers
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2}, PairOptions::default());
+		let (fetched_pairs, _) = Fixture::fetch_prices(1);
+		assert_eq!(
+			vec![
+				ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER},
+				ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2},
+			].into_iter().collect::<BTreeSet<_>>(),
+			fetched_pairs.into_iter().map(|(pp, _)| pp).collect::<BTreeSet<_>>());
+		assert_eq!(
+			vec![
+				(MOCK_PROVIDER,  BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()),
+				(MOCK_PROVIDER2, b"XBT".to_vec(),       b"USD".to_vec()),
+			].into_iter().collect::<BTreeSet<_>>(),
+			mock_queries().into_iter().collect::<BTreeSet<_>>());
+
+		// ticker already taken by another currency of the same provider
+		assert_noop!(
+			Fixture::submit_provider_tickers(Origin::root(), vec![(MOCK_PROVIDER2, ETH_CURRENCY.to_vec(), Some(b"XBT".to_vec()))]),
+			Error::<Test>::DuplicateProviderTickerError);
+
+		// remapping releases the old ticker, removal reverts to the currency itself
+		assert_ok!(Fixture::submit_provider_tickers(Origin::root(), vec![
+			(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(), Some(b"XXBT".to_vec())),
+			(MOCK_PROVIDER2, ETH_CURRENCY.to_vec(), Some(b"XBT".to_vec())),
+			(MOCK_PROVIDER2, USDT_CURRENCY.to_vec(), None),
+		]));
+		assert_eq!(b"XXBT".to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER2, &BTC_CURRENCY.to_vec()));
+		assert_eq!(Some(ETH_CURRENCY.to_vec()), ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"XBT".to_vec()));
+		assert_eq!(USDT_CURRENCY.to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER2, &USDT_CURRENCY.to_vec()));
+		assert_eq!(None, ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"USD".to_vec()));
+
+		// tickers swapped in a single call, regardless of the order
+		assert_ok!(Fixture::submit_provider_tickers(Origin::root(), vec![
+			(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(), Some(b"XBT".to_vec())),
+			(MOCK_PROVIDER2, ETH_CURRENCY.to_vec(), Some(b"XXBT".to_vec())),
+		]));
+		assert_eq!(b"XBT".to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER2, &BTC_CURRENCY.to_vec()));
+		assert_eq!(b"XXBT".to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER2, &ETH_CURRENCY.to_vec()));
+		assert_eq!(Some(BTC_CURRENCY.to_vec()), ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"XBT".to_vec()));
+		assert_eq!(Some(ETH_CURRENCY.to_vec()), ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"XXBT".to_vec()));
+		assert_eq!(2, ProviderTickerCurrencies::<Test>::iter_prefix(MOCK_PROVIDER2).count());
+
+		// duplicate currencies or tickers within the batch are rejected
+		assert_noop!(
+			Fixture::submit_provider_tickers(Origin::root(), vec![
+				(MOCK_PROVIDER2, USDT_CURRENCY.to_vec(), Some(b"USD".to_vec())),
+				(MOCK_PROVIDER2, USDT_CURRENCY.to_vec(), Some(b"ZUSD".to_vec())),
+			]),
+			Error::<Test>::DuplicateProviderCurrencyError);
+		assert_noop!(
+			Fixture::submit_provider_tickers(Origin::root(), vec![
+				(MOCK_PROVIDER2, USDT_CURRENCY.to_vec(), Some(b"USD".to_vec())),
+				(MOCK_PROVIDER2, BOGUS_CURRENCY.to_vec(), Some(b"USD".to_vec())),
+			]),
+			Error::<Test>::DuplicateProviderTickerError);
+		// the same currency of different providers is fine
+		assert_ok!(Fixture::submit_provider_tickers(Origin::root(), vec![
+			(MOCK_PROVIDER, USDT_CURRENCY.to_vec(), Some(b"USD".to_vec())),
+			(MOCK_PROVIDER2, USDT_CURRENCY.to_vec(), Some(b"USD".to_vec())),
+		]));
+	});
+}
+
+#[test]
+fn test_submit_tolerances() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		let tolerance = |source: &[u8], target: &[u8]| Fixture::price_change_tolerance(&source.to_vec(), &target.to_vec());
+		assert_eq!(1, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+
+		let groups = vec![
+			(BTC_CURRENCY.to_vec(), Some(b"volatile".to_vec())),
+			(USDT_CURRENCY.to_vec(), Some(b"stable".to_vec())),
+		];
+		assert_noop!(Fixture::submit_currency_groups(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), groups.clone()), BadOrigin);
+		assert_ok!(Fixture::submit_currency_groups(Origin::root(), groups.clone()));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::CurrencyGroupsSubmitted(groups))));
+		// groups without tolerances default to the global one
+		assert_eq!(1, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+
+		let group_tolerances = vec![(b"volatile".to_vec(), Some(50_000)), (b"stable".to_vec(), Some(100))];
+		assert_noop!(Fixture::submit_group_tolerances(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), group_tolerances.clone()), BadOrigin);
+		assert_ok!(Fixture::submit_group_tolerances(Origin::root(), group_tolerances.clone()));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::GroupTolerancesSubmitted(group_tolerances))));
+		// looser of the currency tolerances, ungrouped currencies at the global one
+		assert_eq!(50_000, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+		assert_eq!(100, tolerance(ETH_CURRENCY, USDT_CURRENCY));
+		assert_eq!(1, tolerance(ETH_CURRENCY, BOGUS_CURRENCY));
+
+		// pair overrides take precedence, per direction
+		let pair_tolerances = vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(10))];
+		assert_noop!(Fixture::submit_pair_tolerances(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), pair_tolerances.clone()), BadOrigin);
+		assert_ok!(Fixture::submit_pair_tolerances(Origin::root(), pair_tolerances.clone()));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::PairTolerancesSubmitted(pair_tolerances))));
+		assert_eq!(10, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+		assert_eq!(50_000, tolerance(USDT_CURRENCY, BTC_CURRENCY));
+
+		// removals
+		assert_ok!(Fixture::submit_pair_tolerances(Origin::root(), vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		assert_eq!(50_000, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+		assert_ok!(Fixture::submit_currency_groups(Origin::root(), vec![(BTC_CURRENCY.to_vec(), None)]));
+		assert_eq!(100, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+		assert_ok!(Fixture::submit_group_tolerances(Origin::root(), vec![(b"stable".to_vec(), None)]));
+		assert_eq!(1, tolerance(BTC_CURRENCY, USDT_CURRENCY));
+	});
+}
+
+#[test]
+fn test_calc_best_path_changes_tolerance_and_max_age() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let stored = |total_cost| StoredPricePath{path: PricePath{total_cost, steps: vec![]}, step_quotes: vec![], confidence: 0, updated_at: 1};
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), stored(50_000));
+		BestPaths::<Test>::insert(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), stored(1_000));
+		let fetched_pairs = vec![
+			(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, 50_010),  // 0.02% move
+			(ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER}, 1_000),
+		];
+		let changed = |block_number| Fixture::calc_best_path_changes(&fetched_pairs, block_number).unwrap()
+			.into_iter()
+			.map(|(source, _, _)| source)
+			.collect::<Vec<_>>();
+
+		// global tolerance breached
+		assert_eq!(vec![BTC_CURRENCY.to_vec()], changed(5));
+
+		// within the group tolerance
+		assert_ok!(Fixture::submit_currency_groups(Origin::root(), vec![(BTC_CURRENCY.to_vec(), Some(b"volatile".to_vec()))]));
+		assert_ok!(Fixture::submit_group_tolerances(Origin::root(), vec![(b"volatile".to_vec(), Some(1_000))]));
+		assert!(changed(5).is_empty());
+
+		// breaching the pair tolerance
+		assert_ok!(Fixture::submit_pair_tolerances(Origin::root(), vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(100))]));
+		assert_eq!(vec![BTC_CURRENCY.to_vec()], changed(5));
+		assert_ok!(Fixture::submit_pair_tolerances(Origin::root(), vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+
+		// paths beyond max age updated regardless of the tolerance
+		crate::mock::MaxPathAge::set(10);
+		assert!(changed(10).is_empty());
+		assert_eq!(
+			vec![BTC_CURRENCY.to_vec(), ETH_CURRENCY.to_vec()].into_iter().collect::<BTreeSet<_>>(),
+			changed(11).into_iter().collect::<BTreeSet<_>>());
+		crate::mock::MaxPathAge::set(0);
+		assert!(changed(11).is_empty());
+	});
+}
+
+#[test]
+fn test_aggregate_prices() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let btc_usdt = Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()};
+		let eth_usdt = Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()};
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: btc_usdt.clone(), provider: MOCK_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: btc_usdt.clone(), provider: MOCK_PROVIDER2}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: eth_usdt.clone(), provider: MOCK_PROVIDER2}, PairOptions::default());
+
+		let mid = |price| StepQuote{bid: price, ask: price};
+
+		// providers within the deviation are aggregated into a single edge per pair, confident as per their agreement and dispersion
+		set_mock_price(MOCK_PROVIDER2, 50_500);
+		let (fetched_quotes, _) = Fixture::fetch_prices(1);
+		assert_eq!(3, fetched_quotes.len());
+		assert_eq!(
+			vec![
+				(ProviderPair{pair: btc_usdt.clone(), provider: MOCK_PROVIDER},  mid(50_250), 950_250),
+				(ProviderPair{pair: eth_usdt.clone(), provider: MOCK_PROVIDER2}, mid(50_500), 500_000),
+			],
+			Fixture::aggregate_prices(fetched_quotes));
+
+		// providers disagreeing beyond the deviation are dropped altogether
+		set_mock_price(MOCK_PROVIDER2, 80_000);
+		let (fetched_quotes, _) = Fixture::fetch_prices(2);
+		assert_eq!(
+			vec![(ProviderPair{pair: eth_usdt, provider: MOCK_PROVIDER2}, mid(80_000), 500_000)],
+			Fixture::aggregate_prices(fetched_quotes));
+	});
+}
+
+#[test]
+fn test_aggregate_bid_ask() {
+	new_test_ext().execute_with(|| {
+		let provider_pair = |source: &[u8], target: &[u8], provider| ProviderPair{pair: Pair{source: source.to_vec(), target: target.to_vec()}, provider};
+		let bid_ask = |price, bid, ask| PriceQuote{price, volume: None, bid, ask};
+		crate::mock::DefaultSpread::set(20_000);  // 2%
+		let fetched_quotes = vec![
+			(provider_pair(BTC_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER), bid_ask(50_000, Some(49_900), Some(50_100))),
+			(provider_pair(BTC_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER2), bid_ask(50_500, None, None)),
+			(provider_pair(ETH_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER2), bid_ask(1_000, None, None)),
+			(provider_pair(BTC_CURRENCY, ETH_CURRENCY, MOCK_PROVIDER), bid_ask(105, Some(110), Some(100))),
+		];
+		assert_eq!(
+			vec![
+				// crossed book replaced with the default spread
+				(provider_pair(BTC_CURRENCY, ETH_CURRENCY, MOCK_PROVIDER), StepQuote{bid: 104, ask: 106}, 500_000),
+				// quoted bid/ask shifted to the aggregated price of 50_250
+				(provider_pair(BTC_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER), StepQuote{bid: 50_149, ask: 50_350}, 950_250),
+				// missing bid/ask defaulted to the spread around the price
+				(provider_pair(ETH_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER2), StepQuote{bid: 990, ask: 1_010}, 500_000),
+			],
+			Fixture::aggregate_prices(fetched_quotes));
+		crate::mock::DefaultSpread::set(0);
+	});
+}
+
+/// Sign and submit best path changes, as the OCW would
+fn submit_changes(public_key: &sp_core::sr25519::Public, nonce: u64, changes: Vec<(Vec<u8>, Vec<u8>, Option<PricePath<Vec<u8>, u64, PriceProviderId>>)>) -> DispatchResultWithPostInfo {
+	submit_changes_with_quotes(public_key, nonce, changes, vec![], vec![])
+}
+
+/// Sign and submit best path changes, along with step quotes and path confidences, as the OCW would
+fn submit_changes_with_quotes(
+	public_key: &sp_core::sr25519::Public,
+	nonce: u64,
+	changes: Vec<(Vec<u8>, Vec<u8>, Option<PricePath<Vec<u8>, u64, PriceProviderId>>)>,
+	step_quotes: Vec<(ProviderPair<Vec<u8>, PriceProviderId>, StepQuote<u64>)>,
+	confidences: Vec<(Vec<u8>, Vec<u8>, u32)>) -> DispatchResultWithPostInfo {
+	let payload = BestPathChangesPayload {
+		nonce,
+		block_number: System::block_number(),
+		changes,
+		step_quotes,
+		confidences,
+		provider_outages: vec![],
+		public: <Test as SigningTypes>::Public::from(*public_key),
+	};
+	let signature =
+		<BestPathChangesPayload<
+			<Test as SigningTypes>::Public,
+			<Test as frame_system::Config>::BlockNumber,
+			<Test as Config>::Currency,
+			<Test as Config>::Amount,
+			<Test as Config>::Provider,
+		> as SignedPayload<Test>>::sign::<crypto::AuthId>(&payload).unwrap();
+	Fixture::ocw_submit_best_paths_changes(Origin::none(), payload, signature)
+}
+
+/// Signed OCW call of best path changes
+fn signed_call(public_key: &sp_core::sr25519::Public, nonce: u64, changes: Vec<(Vec<u8>, Vec<u8>, Option<PricePath<Vec<u8>, u64, PriceProviderId>>)>) -> crate::Call<Test> {
+	let payload = BestPathChangesPayload {
+		nonce,
+		block_number: System::block_number(),
+		changes,
+		step_quotes: vec![],
+		confidences: vec![],
+		provider_outages: vec![],
+		public: <Test as SigningTypes>::Public::from(*public_key),
+	};
+	let signature =
+		<BestPathChangesPayload<
+			<Test as SigningTypes>::Public,
+			<Test as frame_system::Config>::BlockNumber,
+			<Test as Config>::Currency,
+			<Test as Config>::Amount,
+			<Test as Config>::Provider,
+		> as SignedPayload<Test>>::sign::<crypto::AuthId>(&payload).unwrap();
+	crate::Call::ocw_submit_best_paths_changes { best_path_change_payload: payload, signature }
+}
+
+/// Sign and submit a BTC -> USDT price change, as the OCW would
+fn submit_btc_usdt_cost(public_key: &sp_core::sr25519::Public, nonce: u64, total_cost: u64) {
+	assert_ok!(submit_changes(public_key, nonce, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost, steps: vec![]}))]));
+}
+
+fn btc_usdt_cost() -> Option<u64> {
+	BestPaths::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()).map(|p| p.path.total_cost)
+}
+
+#[test]
+fn test_suspicious_change_confirmed_by_ocw() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		submit_btc_usdt_cost(public_key, 0, 50_000);  // no previous price, accepted
+		submit_btc_usdt_cost(public_key, 1, 70_000);  // 40% move, accepted
+		assert_eq!(Some(70_000), btc_usdt_cost());
+
+		// 200% move, held
+		submit_btc_usdt_cost(public_key, 2, 210_000);
+		assert_eq!(Some(70_000), btc_usdt_cost());
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::SuspiciousChangeHeld(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 70_000, 210_000))));
+
+		// same block resubmission does not confirm
+		submit_btc_usdt_cost(public_key, 3, 210_000);
+		assert_eq!(Some(70_000), btc_usdt_cost());
+
+		// subsequent round with a different extreme price replaces the held change
+		System::set_block_number(2);
+		submit_btc_usdt_cost(public_key, 4, 7_000);
+		assert_eq!(Some(70_000), btc_usdt_cost());
+		assert_eq!(Some(7_000), PendingSuspiciousChanges::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()).map(|held| held.path.total_cost));
+
+		// subsequent round agreeing with the held change, released
+		System::set_block_number(3);
+		submit_btc_usdt_cost(public_key, 5, 7_500);
+		assert_eq!(Some(7_500), btc_usdt_cost());
+		assert_eq!(None, PendingSuspiciousChanges::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert!(System::events().iter().any(|r| r.event == Event::Fixture(crate::Event::<Test>::SuspiciousChangeReleased(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 7_500))));
+
+		// held change made obsolete by a price within bounds
+		System::set_block_number(4);
+		submit_btc_usdt_cost(public_key, 6, 75_000);
+		assert!(PendingSuspiciousChanges::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		submit_btc_usdt_cost(public_key, 7, 8_000);
+		assert_eq!(Some(8_000), btc_usdt_cost());
+		assert!(!PendingSuspiciousChanges::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+	});
+}
+
+#[test]
+fn test_suspicious_change_window() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		submit_btc_usdt_cost(public_key, 0, 50_000);
+		submit_btc_usdt_cost(public_key, 1, 70_000);  // 40% on window start
+		System::set_block_number(5);
+		submit_btc_usdt_cost(public_key, 2, 95_000);  // 36% per update, but 90% on window start, held
+		assert_eq!(Some(70_000), btc_usdt_cost());
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::SuspiciousChangeHeld(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 70_000, 95_000))));
+
+		// window expired, restarts from the current price
+		System::set_block_number(11);
+		submit_btc_usdt_cost(public_key, 3, 95_000);
+		assert_eq!(Some(95_000), btc_usdt_cost());
+		assert_eq!(Some((11, 70_000)), PriceWindowAnchors::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+	});
+}
+
+#[test]
+fn test_approve_reject_suspicious_change() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		assert_noop!(Fixture::approve_suspicious_change(Origin::root(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()), Error::<Test>::SuspiciousChangeNotFoundError);
+		assert_noop!(Fixture::reject_suspicious_change(Origin::root(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()), Error::<Test>::SuspiciousChangeNotFoundError);
+
+		submit_btc_usdt_cost(public_key, 0, 50_000);
+		submit_btc_usdt_cost(public_key, 1, 500_000);
+		assert_noop!(Fixture::approve_suspicious_change(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()), BadOrigin);
+		assert_ok!(Fixture::reject_suspicious_change(Origin::root(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::SuspiciousChangeRejected(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()))));
+		assert_eq!(Some(50_000), btc_usdt_cost());
+
+		submit_btc_usdt_cost(public_key, 2, 500_000);
+		assert_ok!(Fixture::approve_suspicious_change(Origin::root(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::SuspiciousChangeReleased(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 500_000))));
+		assert_eq!(Some(500_000), btc_usdt_cost());
+		assert_eq!(Some((1, 500_000)), PriceWindowAnchors::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+
+		// subsequent moves measured against the approved price
+		submit_btc_usdt_cost(public_key, 3, 600_000);
+		assert_eq!(Some(600_000), btc_usdt_cost());
+	});
+}
+
+#[test]
+fn test_suspicious_change_expiry() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let held_cost = || PendingSuspiciousChanges::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()).map(|held| held.path.total_cost);
+		System::set_block_number(1);
+		submit_btc_usdt_cost(public_key, 0, 50_000);
+		System::set_block_number(2);
+		submit_btc_usdt_cost(public_key, 1, 200_000);
+		assert_eq!(Some(200_000), held_cost());
+
+		// hold expired after SuspiciousChangeTimeout of 5 blocks, no longer confirmed nor approved, but replaced
+		System::set_block_number(8);
+		assert_noop!(Fixture::approve_suspicious_change(Origin::root(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()), Error::<Test>::SuspiciousChangeNotFoundError);
+		submit_btc_usdt_cost(public_key, 2, 205_000);
+		assert_eq!(Some(50_000), btc_usdt_cost());
+		assert_eq!(Some(205_000), held_cost());
+
+		// replaced hold outlives the expiry of the previous one
+		Fixture::on_initialize(8);
+		assert_eq!(Some(205_000), held_cost());
+
+		// confirmed within the timeout
+		System::set_block_number(13);
+		submit_btc_usdt_cost(public_key, 3, 210_000);
+		assert_eq!(Some(210_000), btc_usdt_cost());
+		assert_eq!(None, held_cost());
+
+		// expired hold pruned
+		System::set_block_number(14);
+		submit_btc_usdt_cost(public_key, 4, 21_000);
+		assert_eq!(Some(21_000), held_cost());
+		Fixture::on_initialize(19);
+		assert_eq!(Some(21_000), held_cost());
+		Fixture::on_initialize(20);
+		assert_eq!(None, held_cost());
+		assert!(!SuspiciousChangeExpiry::<Test>::contains_key(20));
+	});
+}
+
+#[test]
+fn test_remove_best_path_discards_suspicious_change() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		submit_btc_usdt_cost(public_key, 0, 50_000);
+		submit_btc_usdt_cost(public_key, 1, 60_000);
+		submit_btc_usdt_cost(public_key, 2, 200_000);
+		assert!(PendingSuspiciousChanges::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert!(PriceWindowAnchors::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+
+		assert_ok!(submit_changes(public_key, 3, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		assert_eq!(None, btc_usdt_cost());
+		assert!(!PendingSuspiciousChanges::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert!(!PriceWindowAnchors::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+
+		// re-added path is not measured against the discarded window, nor confirms the discarded hold
+		System::set_block_number(2);
+		submit_btc_usdt_cost(public_key, 4, 200_000);
+		assert_eq!(Some(200_000), btc_usdt_cost());
+	});
+}
+
+#[test]
+fn test_pause_unpause() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		let btc_usdt_via = |provider, cost| Some(PricePath{total_cost: cost, steps: vec![PathStep{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider, cost}]});
+		let get_price_path = |source: &[u8], target: &[u8]| <Fixture as traits::BestPath<_, _, _>>::get_price_path(source.to_vec(), target.to_vec());
+		assert_noop!(Fixture::pause(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), None), BadOrigin);
+		assert_noop!(Fixture::unpause(Origin::signed(sp_core::sr25519::Public([0_u8; 32])), None), BadOrigin);
+
+		// provider pause ignores changes via the provider
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER2)));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::Paused(Some(MOCK_PROVIDER2)))));
+		assert_ok!(submit_changes(public_key, 0, vec![
+			(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), btc_usdt_via(MOCK_PROVIDER2, 50_000)),
+			(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost: 3_000, steps: vec![]})),
+		]));
+		assert_eq!(None, get_price_path(BTC_CURRENCY, USDT_CURRENCY));
+		assert_eq!(Some(3_000), get_price_path(ETH_CURRENCY, USDT_CURRENCY).map(|p| p.total_cost));
+
+		// ...and skips its price fetching
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2}, PairOptions::default());
+		let attempts = mock_fetch_attempts();
+		let (fetched_pairs, _) = Fixture::fetch_prices(1);
+		assert!(fetched_pairs.is_empty());
+		assert_eq!(attempts, mock_fetch_attempts());
+
+		// unpaused provider changes are applied, hidden once paused again, and not removable while paused
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER2)));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::Unpaused(Some(MOCK_PROVIDER2)))));
+		assert_ok!(submit_changes(public_key, 1, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), btc_usdt_via(MOCK_PROVIDER2, 50_000))]));
+		assert_eq!(Some(50_000), get_price_path(BTC_CURRENCY, USDT_CURRENCY).map(|p| p.total_cost));
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER2)));
+		assert_eq!(None, get_price_path(BTC_CURRENCY, USDT_CURRENCY));
+		assert_ok!(submit_changes(public_key, 2, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		assert!(BestPaths::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER2)));
+
+		// global pause rejects all changes
+		assert_ok!(Fixture::pause(Origin::root(), None));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::Paused(None))));
+		assert_eq!(None, get_price_path(ETH_CURRENCY, USDT_CURRENCY));
+		assert_noop!(submit_changes(public_key, 3, vec![(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]), Error::<Test>::PalletPausedError);
+		assert_ok!(Fixture::unpause(Origin::root(), None));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::Unpaused(None))));
+		assert_eq!(Some(3_000), get_price_path(ETH_CURRENCY, USDT_CURRENCY).map(|p| p.total_cost));
+		assert_ok!(submit_changes(public_key, 3, vec![(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		assert_eq!(None, get_price_path(ETH_CURRENCY, USDT_CURRENCY));
+	});
+}
+
+#[test]
+fn test_genesis_config() {
+	let authority = sp_core::sr25519::Public([1_u8; 32]);
+	let btc_meta = CurrencyMeta{symbol: BTC_CURRENCY.to_vec(), decimals: 8, display_name: b"Bitcoin".to_vec()};
+	new_test_ext_with_genesis(crate::GenesisConfig::<Test> {
+		monitored_pairs: vec![
+			(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), MOCK_PROVIDER),
+			(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), MOCK_PROVIDER2),
+		],
+		authorities: vec![authority],
+		currency_metadata: vec![(BTC_CURRENCY.to_vec(), btc_meta.clone())],
+		provider_tickers: vec![(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(), b"XBT".to_vec())],
+		best_paths: vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 50_000, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), MOCK_PROVIDER, 50_000)])],
+	}).execute_with(|| {
+		assert_eq!(
+			vec![
+				ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER},
+				ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2},
+			].into_iter().collect::<BTreeSet<_>>(),
+			MonitoredPairs::<Test>::iter_keys().collect::<BTreeSet<_>>());
+		assert!(WhitelistedOffchainAuthorities::<Test>::contains_key(authority));
+		assert_eq!(Some(btc_meta), CurrencyMetadata::<Test>::get(BTC_CURRENCY.to_vec()));
+		assert_eq!(b"XBT".to_vec(), Fixture::provider_ticker(&MOCK_PROVIDER2, &BTC_CURRENCY.to_vec()));
+		assert_eq!(Some(BTC_CURRENCY.to_vec()), ProviderTickerCurrencies::<Test>::get(MOCK_PROVIDER2, b"XBT".to_vec()));
+		assert_eq!(
+			Some(StoredPricePath{path: PricePath{total_cost: 50_000, steps: vec![PathStep{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER, cost: 50_000}]}, step_quotes: vec![StepQuote{bid: 50_000, ask: 50_000}], confidence: 0, updated_at: 0}),
+			BestPaths::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+	});
+
+	// default genesis leaves the pallet empty
+	new_test_ext_with_genesis(Default::default()).execute_with(|| {
+		assert_eq!(0, MonitoredPairs::<Test>::iter_keys().count());
+		assert_eq!(0, WhitelistedOffchainAuthorities::<Test>::iter_keys().count());
+		assert_eq!(0, BestPaths::<Test>::iter_keys().count());
+	});
+}
+
+#[test]
+#[should_panic(expected = "Provider ticker mapped to multiple currencies")]
+fn test_genesis_config_duplicate_ticker() {
+	new_test_ext_with_genesis(crate::GenesisConfig::<Test> {
+		provider_tickers: vec![
+			(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(), b"XBT".to_vec()),
+			(MOCK_PROVIDER2, ETH_CURRENCY.to_vec(), b"XBT".to_vec()),
+		],
+		..Default::default()
+	});
+}
+
+#[test]
+#[should_panic(expected = "Currency mapped to multiple provider tickers")]
+fn test_genesis_config_duplicate_currency() {
+	new_test_ext_with_genesis(crate::GenesisConfig::<Test> {
+		provider_tickers: vec![
+			(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(), b"XBT".to_vec()),
+			(MOCK_PROVIDER2, BTC_CURRENCY.to_vec(), b"XXBT".to_vec()),
+		],
+		..Default::default()
+	});
+}
+
+#[test]
+fn test_migrate_to_v1() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(5);
+		StorageVersion::new(0).put::<Fixture>();
+		let old_path: PricePath<Vec<u8>, u64, PriceProviderId> = PricePath{total_cost: 50_000, steps: vec![PathStep{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER, cost: 50_000}]};
+		frame_support::storage::unhashed::put(&BestPaths::<Test>::hashed_key_for(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()), &old_path);
+		let btc_usdt = ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER};
+		frame_support::storage::unhashed::put(&MonitoredPairs::<Test>::hashed_key_for(&btc_usdt), &());
+
+		// migrated through all versions
+		assert!(migrations::migrate::<Test>() > 0);
+		assert_eq!(crate::pallet::STORAGE_VERSION, Fixture::on_chain_storage_version());
+		let stored_path = StoredPricePath{path: old_path, step_quotes: vec![], confidence: 0, updated_at: 5};
+		assert_eq!(Some(stored_path.clone()), BestPaths::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_eq!(Some(PairOptions{bidirectional: false}), MonitoredPairs::<Test>::get(&btc_usdt));
+
+		// subsequent runs are noops
+		System::set_block_number(6);
+		migrations::v1::MigrateToV1::<Test>::on_runtime_upgrade();
+		assert_eq!(Some(stored_path), BestPaths::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+	});
+}
+
+#[test]
+fn test_try_state() {
+	let scaled = |cost: u64| cost * 1_000_000_000_000;  // PriceScale of 12
+	let btc_eth = Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()};
+	let eth_usdt = Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()};
+	let btc_usdt_path = |total_cost, steps: Vec<(Pair<Vec<u8>>, u64)>| StoredPricePath{
+		path: PricePath{total_cost, steps: steps.into_iter().map(|(pair, cost)| PathStep{pair, provider: MOCK_PROVIDER, cost}).collect()},
+		step_quotes: vec![],
+		confidence: 0,
+		updated_at: 1,
+	};
+	new_test_ext_with_genesis(crate::GenesisConfig::<Test> {
+		monitored_pairs: vec![
+			(BTC_CURRENCY.to_vec(), ETH_CURRENCY.to_vec(), MOCK_PROVIDER),
+			(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), MOCK_PROVIDER),
+		],
+		authorities: vec![<sp_core::sr25519::Pair as sp_core::Pair>::from_seed(&[1_u8; 32]).public()],
+		best_paths: vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), scaled(50_000), vec![
+			(BTC_CURRENCY.to_vec(), ETH_CURRENCY.to_vec(), MOCK_PROVIDER, scaled(20)),
+			(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), MOCK_PROVIDER, scaled(2_500)),
+		])],
+		..Default::default()
+	}).execute_with(|| {
+		assert_ok!(Fixture::do_try_state());
+
+		// authorities that aren't valid keys could never sign, eg. of an odd, ie. negative, ristretto encoding
+		let invalid_authority = sp_core::sr25519::Public([1_u8; 32]);
+		WhitelistedOffchainAuthorities::<Test>::insert(invalid_authority, ());
+		assert_eq!(Err("Whitelisted authority is not a valid sr25519 key"), Fixture::do_try_state());
+		WhitelistedOffchainAuthorities::<Test>::remove(invalid_authority);
+		assert_ok!(Fixture::do_try_state());
+
+		// paths without steps are not verified
+		BestPaths::<Test>::insert(ETH_CURRENCY.to_vec(), BTC_CURRENCY.to_vec(), StoredPricePath{path: PricePath{total_cost: 1, steps: vec![]}, step_quotes: vec![], confidence: 0, updated_at: 1});
+		assert_ok!(Fixture::do_try_state());
+
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), btc_usdt_path(scaled(60_000), vec![(btc_eth.clone(), scaled(20)), (eth_usdt.clone(), scaled(2_500))]));
+		assert_eq!(Err("Best path total cost does not match its steps"), Fixture::do_try_state());
+
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), btc_usdt_path(scaled(2_500), vec![(eth_usdt.clone(), scaled(2_500))]));
+		assert_eq!(Err("Best path steps not connected"), Fixture::do_try_state());
+
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), btc_usdt_path(scaled(20), vec![(btc_eth.clone(), scaled(20))]));
+		assert_eq!(Err("Best path steps do not reach the target"), Fixture::do_try_state());
+
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), btc_usdt_path(scaled(50_000), vec![(btc_eth.clone(), scaled(20)), (eth_usdt, scaled(2_500))]));
+		assert_ok!(Fixture::do_try_state());
+		MonitoredPairs::<Test>::remove(ProviderPair{pair: btc_eth, provider: MOCK_PROVIDER});
+		assert_eq!(Err("Best path step references unmonitored pair"), Fixture::do_try_state());
+	});
+}
+
+#[test]
+fn test_on_best_path_changed() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		System::set_block_number(1);
+		let eth_usdt = |cost| (ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost: cost, steps: vec![]}));
+		assert_ok!(submit_changes(public_key, 0, vec![eth_usdt(3_000)]));
+		assert_ok!(submit_changes(public_key, 1, vec![eth_usdt(3_100), (BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));  // non existent removal is not notified
+		assert_ok!(submit_changes(public_key, 2, vec![(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		// held suspicious changes are notified once released
+		assert_ok!(submit_changes(public_key, 3, vec![eth_usdt(3_000)]));
+		assert_ok!(submit_changes(public_key, 4, vec![eth_usdt(30_000)]));
+		assert_ok!(Fixture::approve_suspicious_change(Origin::root(), ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_eq!(
+			vec![
+				(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None, Some(3_000)),
+				(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(3_000), Some(3_100)),
+				(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(3_100), None),
+				(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None, Some(3_000)),
+				(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(3_000), Some(30_000)),
+			],
+			mock_path_changes());
+	});
+}
+
+#[test]
+fn test_convert() {
+	new_test_ext().execute_with(|| {
+		let scaled = |cost: u64| cost * 1_000_000_000_000;  // PriceScale of 12
+		let path = |total_cost| PricePath{total_cost, steps: vec![]};
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), StoredPricePath{path: path(scaled(50_000)), step_quotes: vec![], confidence: 0, updated_at: 1});
+
+		assert_eq!(Some(150_000), <Fixture as traits::BestPath<_, _, _>>::convert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 3));
+		assert_eq!(Some(3), <Fixture as traits::BestPath<_, _, _>>::convert(BTC_CURRENCY.to_vec(), BTC_CURRENCY.to_vec(), 3));
+		assert_eq!(None, <Fixture as traits::BestPath<_, _, _>>::convert(USDT_CURRENCY.to_vec(), BTC_CURRENCY.to_vec(), 3));
+
+		// rounding, 3 * 1.5 = 4.5, 1 * 1.25 = 1.25
+		let convert_with_path = |cost, amount, rounding| <Fixture as traits::BestPath<_, _, _>>::convert_with_path(&BTC_CURRENCY.to_vec(), &USDT_CURRENCY.to_vec(), &path(cost), amount, rounding);
+		assert_eq!(Some(4), convert_with_path(1_500_000_000_000, 3, Rounding::Down));
+		assert_eq!(Some(5), convert_with_path(1_500_000_000_000, 3, Rounding::Up));
+		assert_eq!(Some(5), convert_with_path(1_500_000_000_000, 3, Rounding::Nearest));
+		assert_eq!(Some(1), convert_with_path(1_250_000_000_000, 1, Rounding::Down));
+		assert_eq!(Some(2), convert_with_path(1_250_000_000_000, 1, Rounding::Up));
+		assert_eq!(Some(1), convert_with_path(1_250_000_000_000, 1, Rounding::Nearest));
+		assert_eq!(Some(0), convert_with_path(1, 1, Rounding::Down));
+		assert_eq!(Some(1), convert_with_path(1, 1, Rounding::Up));
+
+		// overflow of the amount type
+		assert_eq!(Some(u64::MAX), convert_with_path(scaled(1), u64::MAX, Rounding::Up));
+		assert_eq!(None, convert_with_path(scaled(2), u64::MAX, Rounding::Down));
+		assert_eq!(None, convert_with_path(scaled(1) + 1, u64::MAX, Rounding::Up));
+
+		// costs are prices of whole units, native amounts are converted as per the currency decimals,
+		// eg. 1.5 ETH of 18 decimals at 2,000.5 = 3,000.75 USDT of 6 decimals, and a price below a native unit, of 0.00001
+		let eth_meta = CurrencyMeta{symbol: ETH_CURRENCY.to_vec(), decimals: 18, display_name: b"Ether".to_vec()};
+		let usdt_meta = CurrencyMeta{symbol: USDT_CURRENCY.to_vec(), decimals: 6, display_name: b"Tether".to_vec()};
+		assert_ok!(Fixture::submit_currency_metadata(Origin::root(), ETH_CURRENCY.to_vec(), Some(eth_meta)));
+		assert_ok!(Fixture::submit_currency_metadata(Origin::root(), USDT_CURRENCY.to_vec(), Some(usdt_meta)));
+		BestPaths::<Test>::insert(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), StoredPricePath{path: path(2_000_500_000_000_000), step_quotes: vec![], confidence: 0, updated_at: 1});
+		assert_eq!(Some(3_000_750_000), <Fixture as traits::BestPath<_, _, _>>::convert(ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 1_500_000_000_000_000_000));
+		let convert_eth_usdt = |cost, amount, rounding| <Fixture as traits::BestPath<_, _, _>>::convert_with_path(&ETH_CURRENCY.to_vec(), &USDT_CURRENCY.to_vec(), &path(cost), amount, rounding);
+		assert_eq!(Some(10), convert_eth_usdt(10_000_000, 1_000_000_000_000_000_000, Rounding::Down));
+		assert_eq!(Some(0), convert_eth_usdt(10_000_000, 1_000, Rounding::Down));
+		assert_eq!(Some(1), convert_eth_usdt(10_000_000, 1_000, Rounding::Up));
+		// and back, 3,000 USDT at 1 / 2,000.5 = 1.499625093 ETH
+		let convert_usdt_eth = |cost, amount, rounding| <Fixture as traits::BestPath<_, _, _>>::convert_with_path(&USDT_CURRENCY.to_vec(), &ETH_CURRENCY.to_vec(), &path(cost), amount, rounding);
+		assert_eq!(Some(1_499_625_093_000_000_000), convert_usdt_eth(499_875_031, 3_000_000_000, Rounding::Down));
+
+		// paths hidden while paused aren't converted
+		assert_ok!(Fixture::pause(Origin::root(), None));
+		assert_eq!(None, <Fixture as traits::BestPath<_, _, _>>::convert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 3));
+	});
+}
+
+#[test]
+fn test_execute_best_path() {
+	new_test_ext().execute_with(|| {
+		let who = sp_core::sr25519::Public([1_u8; 32]);
+		let step = |source: &[u8], target: &[u8], provider| PathStep{pair: Pair{source: source.to_vec(), target: target.to_vec()}, provider, cost: 0};
+		let execute = |amount_in, min_amount_out| Fixture::execute_best_path(Origin::signed(who), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), amount_in, min_amount_out);
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), StoredPricePath{
+			path: PricePath{total_cost: 0, steps: vec![step(BTC_CURRENCY, ETH_CURRENCY, MOCK_PROVIDER), step(ETH_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER2)]},
+			step_quotes: vec![],
+			confidence: 0,
+			updated_at: 1,
+		});
+		MockDex::set_rate(&MOCK_PROVIDER, BTC_CURRENCY, ETH_CURRENCY, Some(20_000));
+		MockDex::set_rate(&MOCK_PROVIDER2, ETH_CURRENCY, USDT_CURRENCY, Some(2_500_000));
+		MockDex::set_balance(&who, BTC_CURRENCY, 10);
+
+		assert_noop!(Fixture::execute_best_path(Origin::none(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 10, 0), BadOrigin);
+		assert_noop!(Fixture::execute_best_path(Origin::signed(who), USDT_CURRENCY.to_vec(), BTC_CURRENCY.to_vec(), 10, 0), Error::<Test>::PricePairNotFoundError);
+
+		// failures revert all hops
+		assert_noop!(execute(10, 500_001), Error::<Test>::SlippageExceededError);
+		assert_noop!(execute(11, 0), DispatchError::Other("Insufficient balance"));
+		MockDex::set_rate(&MOCK_PROVIDER2, ETH_CURRENCY, USDT_CURRENCY, None);
+		assert_noop!(execute(10, 0), DispatchError::Other("No pool"));
+		MockDex::set_rate(&MOCK_PROVIDER2, ETH_CURRENCY, USDT_CURRENCY, Some(2_500_000));
+
+		// paused paths aren't executed
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER2)));
+		assert_noop!(execute(10, 0), Error::<Test>::PalletPausedError);
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER2)));
+
+		assert_ok!(execute(10, 500_000));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::BestPathExecuted(who, BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 10, 500_000))));
+		assert_eq!(0, MockDex::balance(&who, BTC_CURRENCY));
+		assert_eq!(0, MockDex::balance(&who, ETH_CURRENCY));
+		assert_eq!(500_000, MockDex::balance(&who, USDT_CURRENCY));
+
+		// paths without steps, or with too many steps, aren't executable
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), StoredPricePath{path: PricePath{total_cost: 0, steps: vec![]}, step_quotes: vec![], confidence: 0, updated_at: 1});
+		assert_noop!(execute(10, 0), Error::<Test>::PathNotExecutableError);
+		BestPaths::<Test>::insert(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), StoredPricePath{
+			path: PricePath{total_cost: 0, steps: vec![step(BTC_CURRENCY, ETH_CURRENCY, MOCK_PROVIDER), step(ETH_CURRENCY, BTC_CURRENCY, MOCK_PROVIDER), step(BTC_CURRENCY, USDT_CURRENCY, MOCK_PROVIDER)]},
+			step_quotes: vec![],
+			confidence: 0,
+			updated_at: 1,
+		});
+		assert_noop!(execute(10, 0), Error::<Test>::PathNotExecutableError);
+
+		// executor hops are charged on top of the pallet weight
+		let call = Call::Fixture(crate::Call::execute_best_path{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec(), amount_in: 10, min_amount_out: 0});
+		assert_eq!(<() as WeightInfo>::execute_best_path(2) + 2 * MOCK_EXECUTE_WEIGHT, call.get_dispatch_info().weight);
+	});
+}
+
+#[test]
+fn test_fetch_dex_prices() {
+	let (t, _, _, _) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let btc_eth = Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()};
+		let eth_btc = Pair{source: ETH_CURRENCY.to_vec(), target: BTC_CURRENCY.to_vec()};
+		let eth_usdt = Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()};
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: btc_eth.clone(), provider: MOCK_DEX_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: eth_btc.clone(), provider: MOCK_DEX_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: eth_usdt.clone(), provider: MOCK_PROVIDER}, PairOptions::default());
+		MonitoredPairs::<Test>::insert(ProviderPair{pair: Pair{source: BOGUS_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_DEX_PROVIDER}, PairOptions::default());  // no pool
+		set_mock_pool(BTC_CURRENCY, ETH_CURRENCY, 10, 200);
+
+		// pool prices mixed with offchain fetched prices, without HTTP queries for the pools
+		let (mut fetched_quotes, _) = Fixture::fetch_prices(1);
+		fetched_quotes.sort_by(|(pp1, _), (pp2, _)| pp1.cmp(pp2));
+		assert_eq!(
+			vec![
+				(ProviderPair{pair: btc_eth.clone(), provider: MOCK_DEX_PROVIDER}, PriceQuote{price: 20_000_000_000_000, volume: Some(10), bid: None, ask: None}),
+				(ProviderPair{pair: eth_btc.clone(), provider: MOCK_DEX_PROVIDER}, PriceQuote{price: 50_000_000_000, volume: Some(200), bid: None, ask: None}),
+				(ProviderPair{pair: eth_usdt, provider: MOCK_PROVIDER}, PriceQuote::of_price(50_000)),
+			],
+			fetched_quotes);
+		assert_eq!(vec![(MOCK_PROVIDER, ETH_CURRENCY.to_vec(), USDT_CURRENCY.to_vec())], mock_queries());
+
+		// pool reserves in native units, prices and volumes in whole units
+		let meta = |symbol: &[u8], decimals| CurrencyMeta{symbol: symbol.to_vec(), decimals, display_name: symbol.to_vec()};
+		CurrencyMetadata::<Test>::insert(BTC_CURRENCY.to_vec(), meta(BTC_CURRENCY, 8));
+		CurrencyMetadata::<Test>::insert(ETH_CURRENCY.to_vec(), meta(ETH_CURRENCY, 6));
+		set_mock_pool(BTC_CURRENCY, ETH_CURRENCY, 1_000_000_000, 200_000_000);
+		let (fetched_quotes, _) = Fixture::fetch_prices(1);
+		let dex_quote = |pair: &Pair<Vec<u8>>| fetched_quotes.iter().find(|(pp, _)| pp.pair == *pair && pp.provider == MOCK_DEX_PROVIDER).map(|(_, quote)| quote.clone());
+		assert_eq!(Some(PriceQuote{price: 20_000_000_000_000, volume: Some(10), bid: None, ask: None}), dex_quote(&btc_eth));
+		assert_eq!(Some(PriceQuote{price: 50_000_000_000, volume: Some(200), bid: None, ask: None}), dex_quote(&eth_btc));
+	});
+}
+
+#[test]
+fn test_submit_bidirectional_pairs() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+		let btc_usdt = ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER};
+		let add = |bidirectional| Fixture::submit_monitored_pairs(Origin::root(), vec![ProviderPairOperation{provider_pair: btc_usdt.clone(), operation: Operation::Add, bidirectional}]);
+		let submitted_event = Some(Event::Fixture(crate::Event::<Test>::MonitoredPairsSubmitted(vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), MOCK_PROVIDER, Operation::Add)])));
+
+		assert_ok!(add(true));
+		assert_eq!(Some(PairOptions{bidirectional: true}), MonitoredPairs::<Test>::get(&btc_usdt));
+		assert_eq!(submitted_event, last_event());
+
+		// unchanged options are skipped in the event
+		System::reset_events();
+		assert_ok!(add(true));
+		assert_eq!(None, last_event());
+
+		// changed options are upserted
+		assert_ok!(add(false));
+		assert_eq!(Some(PairOptions{bidirectional: false}), MonitoredPairs::<Test>::get(&btc_usdt));
+		assert_eq!(submitted_event, last_event());
+	});
+}
+
+#[test]
+fn test_derive_inverse_pairs() {
+	let scaled = |cost: u64| cost * 1_000_000_000_000;  // PriceScale of 12
+	let provider_pair = |source: &[u8], target: &[u8]| ProviderPair{pair: Pair{source: source.to_vec(), target: target.to_vec()}, provider: MOCK_PROVIDER};
+	new_test_ext().execute_with(|| {
+		MonitoredPairs::<Test>::insert(provider_pair(BTC_CURRENCY, USDT_CURRENCY), PairOptions{bidirectional: true});
+		MonitoredPairs::<Test>::insert(provider_pair(ETH_CURRENCY, USDT_CURRENCY), PairOptions{bidirectional: false});
+		MonitoredPairs::<Test>::insert(provider_pair(BTC_CURRENCY, ETH_CURRENCY), PairOptions{bidirectional: true});
+		MonitoredPairs::<Test>::insert(provider_pair(ETH_CURRENCY, BTC_CURRENCY), PairOptions{bidirectional: false});
+		let quote = |bid, ask| StepQuote{bid, ask};
+		let fetched_quotes = vec![
+			(provider_pair(BTC_CURRENCY, USDT_CURRENCY), quote(scaled(4), scaled(5)), 900_000),
+			(provider_pair(ETH_CURRENCY, USDT_CURRENCY), quote(scaled(2), scaled(2)), 800_000),
+			(provider_pair(BTC_CURRENCY, ETH_CURRENCY), quote(scaled(20), scaled(20)), 700_000),
+			(provider_pair(ETH_CURRENCY, BTC_CURRENCY), quote(scaled(1) / 25, scaled(1) / 25), 600_000),
+		];
+
+		// inverse bid of 1/5 less 1% haircut, inverse ask of 1/4, at the same confidence, already fetched ETH -> BTC retained
+		assert_eq!(
+			[fetched_quotes.clone(), vec![(provider_pair(USDT_CURRENCY, BTC_CURRENCY), quote(198_000_000_000, 250_000_000_000), 900_000)]].concat(),
+			Fixture::derive_inverse_pairs(fetched_quotes.clone()));
+
+		// globally derived, regardless of pair options
+		crate::mock::DeriveInversePairs::set(true);
+		assert_eq!(
+			[fetched_quotes.clone(), vec![
+				(provider_pair(USDT_CURRENCY, BTC_CURRENCY), quote(198_000_000_000, 250_000_000_000), 900_000),
+				(provider_pair(USDT_CURRENCY, ETH_CURRENCY), quote(495_000_000_000, 500_000_000_000), 800_000),
+			]].concat(),
+			Fixture::derive_inverse_pairs(fetched_quotes));
+		crate::mock::DeriveInversePairs::set(false);
+
+		// steps via derived inverse edges are deemed monitored
+		BestPaths::<Test>::insert(USDT_CURRENCY.to_vec(), BTC_CURRENCY.to_vec(), StoredPricePath{
+			path: PricePath{total_cost: 198_000_000_000, steps: vec![PathStep{pair: provider_pair(USDT_CURRENCY, BTC_CURRENCY).pair, provider: MOCK_PROVIDER, cost: 198_000_000_000}]},
+			step_quotes: vec![],
+			confidence: 0,
+			updated_at: 1,
+		});
+		assert_ok!(Fixture::do_try_state());
+		BestPaths::<Test>::insert(USDT_CURRENCY.to_vec(), ETH_CURRENCY.to_vec(), StoredPricePath{
+			path: PricePath{total_cost: 495_000_000_000, steps: vec![PathStep{pair: provider_pair(USDT_CURRENCY, ETH_CURRENCY).pair, provider: MOCK_PROVIDER, cost: 495_000_000_000}]},
+			step_quotes: vec![],
+			confidence: 0,
+			updated_at: 1,
+		});
+		assert_eq!(Err("Best path step references unmonitored pair"), Fixture::do_try_state());
+	});
+}
+
+#[test]
+fn test_store_step_quotes() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let btc_eth = ProviderPair{pair: Pair{source: BTC_CURRENCY.to_vec(), target: ETH_CURRENCY.to_vec()}, provider: MOCK_PROVIDER};
+		let eth_usdt = ProviderPair{pair: Pair{source: ETH_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2};
+		let path = |eth_usdt_cost: u64| PricePath{total_cost: 20 * eth_usdt_cost, steps: vec![
+			PathStep{pair: btc_eth.pair.clone(), provider: btc_eth.provider.clone(), cost: 20},
+			PathStep{pair: eth_usdt.pair.clone(), provider: eth_usdt.provider.clone(), cost: eth_usdt_cost},
+		]};
+		let step_quotes = || BestPaths::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()).map(|stored| stored.step_quotes);
+
+		// quotes resolved by step, defaulting to the step cost
+		System::set_block_number(1);
+		assert_ok!(submit_changes_with_quotes(public_key, 0, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(path(2_500)))], vec![
+			(btc_eth.clone(), StepQuote{bid: 20, ask: 21}),
+		], vec![]));
+		assert_eq!(Some(vec![StepQuote{bid: 20, ask: 21}, StepQuote{bid: 2_500, ask: 2_500}]), step_quotes());
+
+		// held changes retain their quotes till approved
+		System::set_block_number(2);
+		assert_ok!(submit_changes_with_quotes(public_key, 1, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(path(5_000)))], vec![
+			(btc_eth.clone(), StepQuote{bid: 20, ask: 22}),
+			(eth_usdt.clone(), StepQuote{bid: 5_000, ask: 5_100}),
+		], vec![]));
+		assert_eq!(Some(vec![StepQuote{bid: 20, ask: 21}, StepQuote{bid: 2_500, ask: 2_500}]), step_quotes());
+		assert_ok!(Fixture::approve_suspicious_change(Origin::root(), BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_eq!(Some(vec![StepQuote{bid: 20, ask: 22}, StepQuote{bid: 5_000, ask: 5_100}]), step_quotes());
+	});
+}
+
+#[test]
+fn test_min_confidence() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let btc_usdt = |total_cost| (BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(PricePath{total_cost, steps: vec![]}));
+		let confidence = || <Fixture as traits::BestPath<_, _, _>>::confidence(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec());
+		let min_confidence_cost = |min_confidence| <Fixture as traits::BestPath<_, _, _>>::get_price_path_with_min_confidence(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), min_confidence).map(|p| p.total_cost);
+		assert_eq!(None, confidence());
+
+		// paths without submitted confidence are of unknown confidence
+		System::set_block_number(1);
+		assert_ok!(submit_changes(public_key, 0, vec![btc_usdt(50_000)]));
+		assert_eq!(Some(0), confidence());
+		assert_eq!(Some(50_000), min_confidence_cost(0));
+		assert_eq!(None, min_confidence_cost(1));
+
+		System::set_block_number(2);
+		assert_ok!(submit_changes_with_quotes(public_key, 1, vec![btc_usdt(51_000)], vec![], vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 800_000)]));
+		assert_eq!(Some(800_000), confidence());
+		assert_eq!(Some(51_000), min_confidence_cost(500_000));
+
+		// confidence decays over ConfidenceDecayPeriod of 10 blocks
+		System::set_block_number(7);
+		assert_eq!(Some(400_000), confidence());
+		assert_eq!(None, min_confidence_cost(500_000));
+		assert_eq!(Some(51_000), min_confidence_cost(400_000));
+		System::set_block_number(12);
+		assert_eq!(Some(0), confidence());
+
+		// capped at full confidence
+		assert_ok!(submit_changes_with_quotes(public_key, 2, vec![btc_usdt(52_000)], vec![], vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), 2_000_000)]));
+		assert_eq!(Some(MAX_CONFIDENCE), confidence());
+
+		// paused paths are hidden regardless of confidence
+		assert_ok!(Fixture::pause(Origin::root(), None));
+		assert_eq!(None, confidence());
+		assert_eq!(None, min_confidence_cost(0));
+	});
+}
+
+#[test]
+fn test_path_history() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let history = || <Fixture as traits::BestPathHistory<_, _, _, _>>::path_history(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec())
+			.into_iter().map(|(block_number, path)| (block_number, path.total_cost)).collect::<Vec<_>>();
+		for (nonce, cost) in [50_000, 51_000, 52_000, 53_000].into_iter().enumerate() {
+			System::set_block_number(nonce as u64 + 1);
+			submit_btc_usdt_cost(public_key, nonce as u64, cost);
+		}
+		// held changes aren't archived
+		submit_btc_usdt_cost(public_key, 4, 500_000);
+		// removals retain the history
+		assert_ok!(submit_changes(public_key, 5, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+
+		// ring buffer of the latest 3 snapshots
+		assert_eq!(vec![(2, 51_000), (3, 52_000), (4, 53_000)], history());
+
+		// pruned after the retention period
+		Fixture::on_initialize(21);
+		assert_eq!(vec![(2, 51_000), (3, 52_000), (4, 53_000)], history());
+		Fixture::on_initialize(22);
+		assert_eq!(vec![(3, 52_000), (4, 53_000)], history());
+		Fixture::on_initialize(23);
+		Fixture::on_initialize(24);
+		assert_eq!(Vec::<(u64, u64)>::new(), history());
+		assert!(!PathHistory::<Test>::contains_key(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()));
+		assert_eq!(0, PathHistoryExpiry::<Test>::iter_keys().count());
+	});
+}
+
+#[test]
+fn test_twap() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let twap = |window_blocks| <Fixture as traits::BestPath<_, _, _>>::twap(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), window_blocks);
+		assert_eq!(None, twap(0));
+		System::set_block_number(10);
+		submit_btc_usdt_cost(public_key, 0, 100);
+		System::set_block_number(20);
+		submit_btc_usdt_cost(public_key, 1, 105);
+		submit_btc_usdt_cost(public_key, 2, 110);  // supersedes the price within the same block
+		System::set_block_number(30);
+		submit_btc_usdt_cost(public_key, 3, 120);
+		// held changes aren't observed
+		submit_btc_usdt_cost(public_key, 4, 1_200);
+
+		System::set_block_number(40);
+		assert_eq!(Some(120), twap(0));
+		assert_eq!(Some(120), twap(10));
+		assert_eq!(Some(115), twap(20));  // 10 blocks at 110, 10 at 120
+		assert_eq!(Some(110), twap(30));  // 10 blocks at 100, 110 and 120 each
+		assert_eq!(Some(112), twap(25));  // 5 blocks at 100, 10 at 110 and 120 each
+		assert_eq!(None, twap(31));  // beyond the history
+		assert_eq!(None, twap(41));
+
+		// paths are hidden while paused, or while any of their providers is paused
+		BestPaths::<Test>::mutate(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), |stored| stored.as_mut().unwrap().path.steps = vec![
+			PathStep{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER, cost: 120},
+		]);
+		assert_ok!(Fixture::pause(Origin::root(), None));
+		assert_eq!(None, twap(10));
+		assert_ok!(Fixture::unpause(Origin::root(), None));
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER2)));
+		assert_eq!(Some(120), twap(10));
+		assert_ok!(Fixture::pause(Origin::root(), Some(MOCK_PROVIDER)));
+		assert_eq!(None, twap(10));
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER)));
+		assert_ok!(Fixture::unpause(Origin::root(), Some(MOCK_PROVIDER2)));
+		assert_eq!(Some(120), twap(10));
+
+		// removal discards the observations
+		assert_ok!(submit_changes(public_key, 5, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		assert_eq!(None, twap(0));
+	});
+}
+
+#[test]
+fn test_twap_many_blocks() {
+	let (t, _, _, public_key) = &mut new_test_ext_with_keystore();
+	t.execute_with(|| {
+		let twap = |window_blocks| <Fixture as traits::BestPath<_, _, _>>::twap(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), window_blocks);
+		// price alternating between 1,000 and 1,100 every block
+		for block_number in 1..=100 {
+			System::set_block_number(block_number);
+			submit_btc_usdt_cost(public_key, block_number - 1, 1_000 + (block_number % 2) * 100);
+		}
+		assert_eq!(64, TwapObservations::<Test>::get(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec()).len());
+		assert_eq!(Some(1_000), twap(0));
+		assert_eq!(Some(1_050), twap(50));
+		assert_eq!(Some(1_050), twap(2));
+		assert_eq!(Some(1_100), twap(1));  // block 99
+
+		// the price persists past the last update
+		System::set_block_number(150);
+		assert_eq!(Some(1_000), twap(50));
+		assert_eq!(Some(1_008), twap(60));  // 55 blocks at 1,000, 5 at 1,100
+
+		// observations beyond MaxTwapObservations are discarded
+		assert_eq!(Some(1_028), twap(113));  // oldest observation at block 37, 32 blocks at 1,100, 81 at 1,000
+		assert_eq!(None, twap(114));
+	});
+}
+
+#[test]
+fn test_index_path_changes() {
+	let (mut t, _, _, public_key) = new_test_ext_with_keystore();
+	let path = PricePath{total_cost: 50_000, steps: vec![PathStep{pair: Pair{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec()}, provider: MOCK_PROVIDER2, cost: 50_000}]};
+	t.execute_with(|| {
+		System::set_block_number(1);
+		assert_ok!(submit_changes(&public_key, 0, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(path.clone()))]));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::BestPricesSubmitted(public_key, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), Some(path.clone()), Operation::Add)]))));
+		System::set_block_number(2);
+		assert_ok!(submit_changes(&public_key, 1, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None)]));
+		assert_eq!(last_event(), Some(Event::Fixture(crate::Event::<Test>::BestPricesSubmitted(public_key, vec![(BTC_CURRENCY.to_vec(), USDT_CURRENCY.to_vec(), None, Operation::Del)]))));
+	});
+
+	t.persist_offchain_overlay();
+	let offchain_db = t.offchain_db();
+	let record = |block_number| offchain_db.get(&Fixture::path_change_index_key(block_number, 0, &BTC_CURRENCY.to_vec(), &USDT_CURRENCY.to_vec()))
+		.map(|encoded| PathChangeRecord::<Vec<u8>, u64, PriceProviderId, u64, sp_core::sr25519::Public>::decode(&mut &encoded[..]).unwrap());
+	assert_eq!(
+		Some(PathChangeRecord{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec(), old_path: None, new_path: Some(path.clone()), block_number: 1, providers: vec![MOCK_PROVIDER2], authority: Some(public_key)}),
+		record(1));
+	assert_eq!(
+		Some(PathChangeRecord{source: BTC_CURRENCY.to_vec(), target: USDT_CURRENCY.to_vec(), old_path: Some(path), new_path: None, block_number: 2, providers: vec![MOCK_PROVIDER2], authority: Some(public_key)}),
+		record(2));
+	assert_eq!(None, record(3));
+}
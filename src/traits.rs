@@ -1,9 +1,98 @@
 use sp_std::vec::Vec;
+use sp_runtime::DispatchError;
+use frame_support::weights::Weight;
 use best_path::prelude::{Currency, Provider, Amount, PricePath};
-use crate::types::ProviderPairOperation;
+use crate::types::{PriceQuote, ProviderPairOperation, Rounding};
 
 /// Trait representing basic, non whitelisted operations, such as submissions of monitored pairs and fetching of price path.
 pub trait BestPath<C: Currency, A: Amount, P: Provider> {
     fn submit_monitored_pairs(operations: Vec<ProviderPairOperation<C, P>>);
     fn get_price_path(source: C, target: C) -> Option<PricePath<C, A, P>>;
-}
\ No newline at end of file
+    /// Confidence of the best path, in 1/1,000,000, as per the dispersion and agreement of its providers, decaying with its age.
+    /// Returns None if no path exists.
+    fn confidence(source: C, target: C) -> Option<u32>;
+    /// Best path as per `get_price_path`, or None if its confidence is below `min_confidence`, expressed in 1/1,000,000.
+    fn get_price_path_with_min_confidence(source: C, target: C, min_confidence: u32) -> Option<PricePath<C, A, P>>;
+    /// Time weighted average best path cost over the last `window_blocks`, resistant to short lived price manipulation.
+    /// Returns None if the pair's price history doesn't span the window.
+    fn twap(source: C, target: C, window_blocks: u32) -> Option<A>;
+    /// Convert `amount` of source currency into target currency, via the best path, rounding down.
+    /// Amounts are in the native units of the currencies, as per their registered decimals.
+    /// Returns None if no path exists or the result overflows.
+    fn convert(source: C, target: C, amount: A) -> Option<A>;
+    /// Convert `amount` of source into target currency via the given path, with the given rounding.
+    /// Path costs are prices of whole units, the decimals of the currencies are applied to the native amounts.
+    /// Returns None if the result overflows.
+    fn convert_with_path(source: &C, target: &C, path: &PricePath<C, A, P>, amount: A, rounding: Rounding) -> Option<A>;
+}
+/// Handler of best path changes, for dependent pallets, eg. to re-check collateral upon price change.
+/// Implemented for tuples of handlers, each invoked in order.
+pub trait OnBestPathChanged<C: Currency, A: Amount, P: Provider> {
+    /// Invoked once the change is stored, with `old` of None on addition, and `new` of None on removal.
+    fn on_best_path_changed(source: &C, target: &C, old: Option<&PricePath<C, A, P>>, new: Option<&PricePath<C, A, P>>);
+    /// Upper bound of the weight of a single `on_best_path_changed`, charged per change on top of the pallet's own weight.
+    fn on_best_path_changed_weight() -> Weight;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(30)]
+impl<C: Currency, A: Amount, P: Provider> OnBestPathChanged<C, A, P> for Tuple {
+    fn on_best_path_changed(source: &C, target: &C, old: Option<&PricePath<C, A, P>>, new: Option<&PricePath<C, A, P>>) {
+        for_tuples!( #( Tuple::on_best_path_changed(source, target, old, new); )* );
+    }
+
+    fn on_best_path_changed_weight() -> Weight {
+        let mut weight: Weight = 0;
+        for_tuples!( #( weight = weight.saturating_add(Tuple::on_best_path_changed_weight()); )* );
+        weight
+    }
+}
+
+/// Executor of best path steps, dispatching each hop to the adapter of the step provider, eg. a DEX pallet.
+pub trait PathExecutor<AccountId, C: Currency, A: Amount, P: Provider> {
+    /// Swap `amount_in` of source into target currency on behalf of `who`, via the provider, returning the amount received.
+    fn execute_step(who: &AccountId, provider: &P, source: &C, target: &C, amount_in: A) -> Result<A, DispatchError>;
+    /// Upper bound of the weight of a single `execute_step`, charged per hop on top of the pallet's own weight.
+    fn execute_weight() -> Weight;
+}
+
+/// No executors, for runtimes that do not execute best paths.
+impl<AccountId, C: Currency, A: Amount, P: Provider> PathExecutor<AccountId, C, A, P> for () {
+    fn execute_step(_who: &AccountId, _provider: &P, _source: &C, _target: &C, _amount_in: A) -> Result<A, DispatchError> {
+        Err(DispatchError::Other("No path executor"))
+    }
+
+    fn execute_weight() -> Weight {
+        0
+    }
+}
+
+/// Source of onchain prices, eg. AMM liquidity pools of the local chain, for pairs not priced by offchain providers.
+pub trait DexPriceSource<C, A> {
+    /// Price of a whole unit of source currency in whole units of target currency, in fixed point of `scale` decimals,
+    /// as converted from the native units of the pool by the given currency decimals.
+    /// Volume, if known, indicates the pool liquidity in whole source currency units, as per `PriceQuote::volume`.
+    /// Returns None if no pool exists.
+    fn get_pool_price(source: &C, target: &C, source_decimals: u8, target_decimals: u8, scale: u32) -> Option<PriceQuote<A>>;
+}
+
+/// No onchain pools.
+impl<C, A> DexPriceSource<C, A> for () {
+    fn get_pool_price(_source: &C, _target: &C, _source_decimals: u8, _target_decimals: u8, _scale: u32) -> Option<PriceQuote<A>> {
+        None
+    }
+}
+
+/// Archive of best paths, for audits and backtesting.
+pub trait BestPathHistory<C: Currency, A: Amount, P: Provider, B> {
+    /// Latest best path snapshots of the pair, with the blocks of their updates, ordered from the oldest.
+    fn path_history(source: C, target: C) -> Vec<(B, PricePath<C, A, P>)>;
+}
+
+/// Runtime specific setup of benchmarks, as the pallet is generic over providers and executors.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AccountId, C: Currency, A: Amount, P: Provider> {
+    /// Provider of the benchmarked monitored pairs and best path steps.
+    fn provider() -> P;
+    /// Fund `who` with `amount_in` of the path source currency and make each of the path steps executable.
+    fn setup_execution(who: &AccountId, path: &PricePath<C, A, P>, amount_in: A);
+}
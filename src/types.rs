@@ -3,11 +3,16 @@ use codec::{Decode, Encode};
 use scale_info::TypeInfo;
 use sp_std::vec::Vec;
 use sp_std::str;
-use best_path::prelude::{Currency, Provider, ProviderPair};
+use sp_runtime::{traits::AtLeast32BitUnsigned, SaturatedConversion};
+use best_path::prelude::{Amount, Currency, PricePath, Provider, ProviderPair};
 
 pub trait Conversions {
     fn to_str(&self) -> &str;
     fn from_vecu8(vec: Vec<u8>) -> Self;
+    /// Display string, preferring the display name from currency metadata, if registered
+    fn to_display_str<'a>(&'a self, metadata: Option<&'a CurrencyMeta>) -> &'a str {
+        metadata.and_then(|m| str::from_utf8(&m.display_name).ok()).unwrap_or_else(|| self.to_str())
+    }
 }
 
 impl Conversions for Vec<u8> {
@@ -23,6 +28,15 @@ impl Conversions for Vec<u8> {
 pub struct ProviderPairOperation<C: Currency, P: Provider> {
     pub provider_pair: ProviderPair<C, P>,
     pub operation: Operation,
+    /// Whether the inverse edge of the pair is to be derived from its price, ignored on deletion
+    pub bidirectional: bool,
+}
+
+/// Options of a monitored pair.
+#[derive(Clone, Default, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct PairOptions {
+	/// Derive the inverse edge, ie. target to source, from the fetched price, less `InversePairHaircut`
+	pub bidirectional: bool,
 }
 
 #[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
@@ -30,3 +44,129 @@ pub enum Operation {
 	Add,
 	Del,
 }
+
+/// Best path change record, written to the offchain index for external indexers.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct PathChangeRecord<C: Currency, A: Amount, P: Provider, B, AccountId> {
+	pub source: C,
+	pub target: C,
+	/// Previous path, None on addition
+	pub old_path: Option<PricePath<C, A, P>>,
+	/// Current path, None on removal
+	pub new_path: Option<PricePath<C, A, P>>,
+	pub block_number: B,
+	/// Providers of the old and new path steps
+	pub providers: Vec<P>,
+	/// Offchain authority submitting the change, None if approved by the admin
+	pub authority: Option<AccountId>,
+}
+
+/// Observation of the cumulative price, ie. the sum of per block prices, for TWAP calculation.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct TwapObservation<B> {
+	pub block_number: B,
+	/// Sum of prices of all blocks prior to `block_number`, wrapping on overflow, as only the differences are meaningful
+	pub cumulative: u128,
+	/// Price as of `block_number`
+	pub price: u128,
+}
+
+impl<B: AtLeast32BitUnsigned + Copy> TwapObservation<B> {
+	/// Cumulative price as of a subsequent block, given the price persisted since the observation
+	pub fn cumulative_at(&self, block_number: B) -> u128 {
+		let elapsed: u128 = block_number.saturating_sub(self.block_number).saturated_into();
+		self.cumulative.wrapping_add(self.price.wrapping_mul(elapsed))
+	}
+}
+
+/// Rounding of fixed point arithmetic results.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub enum Rounding {
+	/// Towards zero, ie. truncation
+	Down,
+	/// Away from zero
+	Up,
+	/// To the nearest, with halves rounded up
+	Nearest,
+}
+
+/// Best path as stored onchain, along with the bid/ask quotes of its steps, its confidence and the block of its latest update.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct StoredPricePath<C: Currency, A: Amount, P: Provider, B> {
+	pub path: PricePath<C, A, P>,
+	/// Quotes of `path.steps`, by step index. Empty for paths stored prior to bid/ask quoting
+	pub step_quotes: Vec<StepQuote<A>>,
+	/// Confidence as of `updated_at`, expressed in 1/1,000,000, derived from the dispersion and agreement of provider prices along the path.
+	/// 0 if unknown, eg. for paths stored prior to confidence tracking
+	pub confidence: u32,
+	pub updated_at: B,
+}
+
+/// Both sides of a best path step, ie. of a graph edge. The step cost is its bid, as each step sells the source for the target currency.
+#[derive(Clone, Copy, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct StepQuote<A> {
+	/// Price of selling the source currency
+	pub bid: A,
+	/// Price of buying the source currency
+	pub ask: A,
+}
+
+/// Currency metadata, registered by the admin.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+#[cfg_attr(feature = "std", derive(frame_support::Serialize, frame_support::Deserialize))]
+#[cfg_attr(feature = "std", serde(crate = "frame_support::serde"))]
+pub struct CurrencyMeta {
+	/// Canonical ticker symbol, eg. `BTC`
+	pub symbol: Vec<u8>,
+	/// Decimals of the native unit, eg. 8 for BTC (satoshi)
+	pub decimals: u8,
+	/// Human readable name, eg. `Bitcoin`
+	pub display_name: Vec<u8>,
+}
+
+/// Price quote of a single provider.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct PriceQuote<A> {
+	/// Mid, or last trade, price
+	pub price: A,
+	/// Traded volume in whole source currency units, if quoted by the provider
+	pub volume: Option<A>,
+	/// Best bid, if quoted by the provider
+	pub bid: Option<A>,
+	/// Best ask, if quoted by the provider
+	pub ask: Option<A>,
+}
+
+impl<A> PriceQuote<A> {
+	/// Quote of the price only, without volume or bid/ask
+	pub fn of_price(price: A) -> Self {
+		PriceQuote { price, volume: None, bid: None, ask: None }
+	}
+
+	pub fn map<B>(self, f: impl Fn(A) -> B) -> PriceQuote<B> {
+		PriceQuote { price: f(self.price), volume: self.volume.map(&f), bid: self.bid.map(&f), ask: self.ask.map(&f) }
+	}
+}
+
+/// Latest price fetch of a monitored pair, as cached by the OCW in the offchain storage.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct CachedQuote<A, BlockNumber> {
+	/// Block of the latest fetch attempt
+	pub attempted_at: BlockNumber,
+	/// Quote of the latest attempt, None if it failed, or once older than `MaxCachedQuoteAge`
+	pub quote: Option<PriceQuote<A>>,
+}
+
+/// Provider health, as tracked by the OCW in the offchain storage.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, Default, TypeInfo)]
+pub struct ProviderHealth<BlockNumber> {
+	pub consecutive_failures: u32,
+	pub skip_until: Option<BlockNumber>,
+}
+
+impl<BlockNumber: PartialOrd> ProviderHealth<BlockNumber> {
+	/// Is the provider to be skipped at the given block, due to recent outage?
+	pub fn is_skipped(&self, block_number: BlockNumber) -> bool {
+		matches!(&self.skip_until, Some(skip_until) if block_number < *skip_until)
+	}
+}
@@ -1,25 +1,129 @@
 use lite_json::json::JsonValue;
-use sp_std::{vec::Vec, str};
+use sp_std::{convert::TryFrom, vec::Vec, str};
+use sp_core::U256;
 use sp_runtime::SaturatedConversion;
+use scale_info::prelude::string::String;
+use crate::{PriceProviderErr, types::Rounding};
 
 /// Parse the price from the given JSON string using `lite-json`.
 ///
-/// Returns `None` when parsing failed or `Some(price in cents)` when parsing is successful.
-pub fn parse_price(price_str: &str, target_currency: &[u8], scale: u32) -> Option<u128> {
-	let val = lite_json::parse_json(price_str);
-	let price = match val.ok()? {
-		JsonValue::Object(obj) => {
-			let (_, v) = obj.into_iter().find(|(k, _)| k.iter().copied().eq(target_currency.iter().map(|b| *b as char)))?;
-			match v {
-				JsonValue::Number(number) => number,
-				_ => return None,
+/// Accepts prices as JSON numbers or decimal strings, including fractions and exponents.
+/// Returns the price scaled by `10^scale` when parsing is successful, or the reason of failure otherwise.
+pub fn parse_price(price_str: &str, target_currency: &[u8], scale: u32) -> Result<u128, PriceProviderErr> {
+	let obj = match lite_json::parse_json(price_str).map_err(|_| PriceProviderErr::ParseErr)? {
+		JsonValue::Object(obj) => obj,
+		_ => return Err(PriceProviderErr::ParseErr),
+	};
+	let price = match json_field(&obj, target_currency) {
+		Some(JsonValue::Number(number)) => {
+			if number.negative {
+				Err(DecimalErr::Negative)
+			} else {
+				10_u128.checked_pow(number.fraction_length)
+					.and_then(|factor| (number.integer as u128).checked_mul(factor))
+					.and_then(|integer| integer.checked_add(number.fraction as u128))
+					.ok_or(DecimalErr::Overflow)
+					.and_then(|mantissa| scale_decimal(mantissa, i64::from(number.exponent) - number.fraction_length as i64, scale))
 			}
 		},
-		_ => return None,
+		Some(JsonValue::String(chars)) => parse_decimal(&chars.iter().collect::<String>(), scale),
+		Some(_) => return Err(PriceProviderErr::ParseErr),
+		None if is_error_response(&obj) => return Err(PriceProviderErr::UnsupportedPairErr),
+		None => return Err(PriceProviderErr::ParseErr),
+	};
+
+	decimal_to_price(price)
+}
+
+/// Validate the parsed decimal as a price, rejecting zero or negative values
+pub fn decimal_to_price(decimal: Result<u128, DecimalErr>) -> Result<u128, PriceProviderErr> {
+	match decimal {
+		Ok(0) | Err(DecimalErr::Negative) => Err(PriceProviderErr::InvalidPriceErr),
+		Ok(price) => Ok(price),
+		Err(_) => Err(PriceProviderErr::ParseErr),
+	}
+}
+
+/// Decimal parsing failures
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecimalErr {
+	/// Not a decimal number
+	Malformed,
+	/// Negative number, where only non negatives are supported
+	Negative,
+	/// Number exceeding u128 once scaled
+	Overflow,
+}
+
+/// Parse the decimal string, eg. `12.789`, `0.00001`, `1.5e-7`, into an integer scaled by `10^scale`.
+///
+/// Digits beyond the `scale` precision are truncated, prior to accumulation, hence fractions of any length are supported.
+pub fn parse_decimal(decimal_str: &str, scale: u32) -> Result<u128, DecimalErr> {
+	let decimal_str = decimal_str.trim();
+	let (negative, unsigned) = match decimal_str.as_bytes().first() {
+		Some(b'-') => (true, &decimal_str[1..]),
+		Some(b'+') => (false, &decimal_str[1..]),
+		_ => (false, decimal_str),
 	};
+	let (significand, exp) = match unsigned.find(|c| c == 'e' || c == 'E') {
+		Some(i) => (&unsigned[..i], unsigned[i + 1..].parse::<i64>().map_err(|_| DecimalErr::Malformed)?),
+		None => (unsigned, 0),
+	};
+	let (integer, fraction) = match significand.find('.') {
+		Some(i) => (&significand[..i], &significand[i + 1..]),
+		None => (significand, ""),
+	};
+	if (integer.is_empty() && fraction.is_empty()) || !integer.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit()) {
+		return Err(DecimalErr::Malformed)
+	}
+
+	// drop the digits that would be truncated by scaling, to keep the mantissa within u128
+	let shift = (scale as i64).saturating_add(exp).saturating_sub(fraction.len() as i64);
+	let digit_count = integer.len() + fraction.len();
+	let (digit_count, shift) = if shift < 0 {
+		(digit_count.saturating_sub(usize::try_from(shift.unsigned_abs()).unwrap_or(usize::MAX)), 0)
+	} else {
+		(digit_count, shift)
+	};
+	let mantissa = integer.bytes().chain(fraction.bytes())
+		.take(digit_count)
+		.try_fold(0_u128, |acc, d| acc.checked_mul(10)?.checked_add((d - b'0') as u128))
+		.ok_or(DecimalErr::Overflow)?;
+
+	match scale_decimal(mantissa, shift, 0)? {
+		0 => Ok(0),
+		_ if negative => Err(DecimalErr::Negative),
+		value => Ok(value),
+	}
+}
+
+/// Scale the decimal `mantissa * 10^exp` into an integer scaled by `10^scale`, truncating the excess precision.
+pub fn scale_decimal(mantissa: u128, exp: i64, scale: u32) -> Result<u128, DecimalErr> {
+	if mantissa == 0 {
+		return Ok(0)
+	}
+	let shift = (scale as i64).saturating_add(exp);
+	let factor = u32::try_from(shift.unsigned_abs()).ok().and_then(|shift| 10_u128.checked_pow(shift));
+	if shift >= 0 {
+		factor.and_then(|factor| mantissa.checked_mul(factor)).ok_or(DecimalErr::Overflow)
+	} else {
+		Ok(factor.map_or(0, |factor| mantissa / factor))
+	}
+}
+
+/// Compare JSON chars with the bytes
+pub fn chars_eq(chars: &[char], bytes: &[u8]) -> bool {
+	chars.iter().copied().eq(bytes.iter().map(|b| *b as char))
+}
+
+/// Lookup JSON object field by key
+pub fn json_field<'a>(obj: &'a [(Vec<char>, JsonValue)], key: &[u8]) -> Option<&'a JsonValue> {
+	obj.iter().find(|(k, _)| chars_eq(k, key)).map(|(_, v)| v)
+}
 
-	let exp = price.fraction_length.saturating_sub(2);
-	Some(price.integer as u128 * 10_u128.pow(scale) + (price.fraction as u128 * 10_u128.pow(scale-2) / 10_u128.pow(exp)))
+/// Check if JSON object is an error response, ie. `{"Response": "Error", ...}`
+fn is_error_response(obj: &[(Vec<char>, JsonValue)]) -> bool {
+	matches!(json_field(obj, b"Response"), Some(JsonValue::String(s)) if chars_eq(s, b"Error"))
 }
 
 /// Concat multiple &[u8]'s together
@@ -33,8 +137,11 @@ pub fn concat(bins: &[&[u8]]) -> Vec<u8> {
     }
 }
 
-/// Check if tolerance breaches the diff
+/// Check if tolerance breaches the diff, any change from zero is deemed a breach
 pub fn breaches_tolerance(old: u128, new: u128, tolerance: u32) -> bool {
+	if old == 0 {
+		return new != 0
+	}
 	let delta = if old > new {
 		1_000_000_u128.saturating_mul((old - new).saturated_into::<u128>()) / old.saturated_into::<u128>()
 	} else {
@@ -43,6 +150,80 @@ pub fn breaches_tolerance(old: u128, new: u128, tolerance: u32) -> bool {
     // log::info!("##### breaches_tolerance: old: {}, new: {}, delta: {}, tolerance: {}", old, new, delta, tolerance);
 	delta > tolerance as u128
 }
+/// Multiply `amount` by a price of `scale` fixed point decimals, with the given rounding.
+///
+/// Intermediate product is widened to 256 bits, hence only fails if the result, or `10^scale`, overflows.
+pub fn mul_scaled(amount: u128, price: u128, scale: u32, rounding: Rounding) -> Option<u128> {
+	let divisor = U256::from(10_u128.checked_pow(scale)?);
+	div_rounded(U256::from(amount) * U256::from(price), divisor, rounding)
+}
+
+/// Convert `amount` of native units of `source_decimals` into native units of `target_decimals`, at a price of whole units
+/// in `scale` fixed point decimals, with the given rounding.
+///
+/// The decimal difference is applied to the 256 bits product, hence no precision is lost before the final rounding.
+/// Only fails if the result, or the decimal scaling, overflows.
+pub fn convert_native(amount: u128, price: u128, scale: u32, source_decimals: u8, target_decimals: u8, rounding: Rounding) -> Option<u128> {
+	let ten = U256::from(10_u8);
+	let product = U256::from(amount) * U256::from(price);
+	let (product, divisor) = if target_decimals >= source_decimals {
+		(product.checked_mul(ten.checked_pow(U256::from(target_decimals - source_decimals))?)?, ten.checked_pow(U256::from(scale))?)
+	} else {
+		(product, ten.checked_pow(U256::from(scale as u64 + (source_decimals - target_decimals) as u64))?)
+	};
+	div_rounded(product, divisor, rounding)
+}
+
+fn div_rounded(dividend: U256, divisor: U256, rounding: Rounding) -> Option<u128> {
+	let (quotient, remainder) = dividend.div_mod(divisor);
+	let round_up = match rounding {
+		Rounding::Down => false,
+		Rounding::Up => !remainder.is_zero(),
+		Rounding::Nearest => remainder >= divisor - remainder,
+	};
+	let quotient = if round_up { quotient.checked_add(U256::one())? } else { quotient };
+	u128::try_from(quotient).ok()
+}
+
+/// Bid and ask around the mid price, `spread` apart, expressed in 1/1,000,000 of the mid price.
+///
+/// Bid is rounded down and ask up, so that a non zero spread never collapses.
+pub fn spread_quote(mid: u128, spread: u32) -> (u128, u128) {
+	let half_spread = U256::from(mid) * U256::from(spread) / U256::from(2_000_000_u32);
+	let half_spread = u128::try_from(half_spread).unwrap_or(u128::MAX);
+	let half_spread_up = if spread > 0 && mid > 0 { half_spread.max(1) } else { half_spread };
+	(mid.saturating_sub(half_spread), mid.saturating_add(half_spread_up))
+}
+
+/// Scale `value` by the ratio of `to` to `from`, rounded down, failing on zero `from` or overflow.
+pub fn rescale(value: u128, from: u128, to: u128) -> Option<u128> {
+	if from == 0 {
+		return None
+	}
+	u128::try_from(U256::from(value) * U256::from(to) / U256::from(from)).ok()
+}
+
+/// Confidence decayed linearly with `age`, down to 0 at `decay_period`. A `decay_period` of 0 disables the decay.
+pub fn decay_confidence(confidence: u32, age: u128, decay_period: u128) -> u32 {
+	if decay_period == 0 {
+		return confidence
+	}
+	(confidence as u128 * decay_period.saturating_sub(age) / decay_period) as u32
+}
+
+/// Inverse of a price of `scale` fixed point decimals, less a `haircut` expressed in 1/1,000,000, rounded down.
+///
+/// Fails on zero price, haircut beyond 1,000,000, or overflow of the result.
+pub fn inverse_price(price: u128, scale: u32, haircut: u32) -> Option<u128> {
+	if price == 0 || haircut > 1_000_000 {
+		return None
+	}
+	let unit = U256::from(10_u128.checked_pow(scale)?);
+	let numerator = (unit * unit).checked_mul(U256::from(1_000_000 - haircut))?;
+	let denominator = U256::from(price) * U256::from(1_000_000_u32);
+	u128::try_from(numerator / denominator).ok()
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(feature = "std")]
@@ -57,13 +238,205 @@ mod tests {
 
     #[test]
     fn test_parse_price() {
-        let payload = r#"{"BTC": 45, "USDT": 12.789, "ETH": 89.000001, "SHIBZELDA": 0.00000007978}"#;
-        assert_eq!(Some(12_789_000_000_000_u128), parse_price(payload, b"USDT", 12));  // FIXME: should round up to 1208?
-        assert_eq!(Some(89_000_001_000_000_u128), parse_price(payload, b"ETH", 12));
-        assert_eq!(Some(45_000_000_000_000_u128), parse_price(payload, b"BTC", 12));
-        assert_eq!(Some(            79_780_u128), parse_price(payload, b"SHIBZELDA", 12));
-        assert_eq!(None,                          parse_price(r#"{"USDT": abc}"#, b"USDT", 12));
-        assert_eq!(None,                          parse_price(r#""USDT": 12"#, b"USDT", 12));
+        let payload = r#"{"BTC": 45, "USDT": 12.789, "ETH": 89.000001, "SHIBZELDA": 0.00000007978, "SHIB": 0.00001, "XRP": 1.5e-7, "KSM": "123.456", "DOGE": 0, "LUNA": -1.5, "BOGUS": 1e30}"#;
+        assert_eq!(Ok(12_789_000_000_000_u128), parse_price(payload, b"USDT", 12));
+        assert_eq!(Ok(89_000_001_000_000_u128), parse_price(payload, b"ETH", 12));
+        assert_eq!(Ok(45_000_000_000_000_u128), parse_price(payload, b"BTC", 12));
+        assert_eq!(Ok(            79_780_u128), parse_price(payload, b"SHIBZELDA", 12));
+        assert_eq!(Ok(        10_000_000_u128), parse_price(payload, b"SHIB", 12));
+        assert_eq!(Ok(           150_000_u128), parse_price(payload, b"XRP", 12));
+        assert_eq!(Ok(123_456_000_000_000_u128), parse_price(payload, b"KSM", 12));
+        assert_eq!(Ok(                45_u128), parse_price(payload, b"BTC", 0));
+        assert_eq!(Ok(               127_u128), parse_price(payload, b"USDT", 1));
+        assert_eq!(Err(PriceProviderErr::InvalidPriceErr),    parse_price(payload, b"SHIB", 2));  // truncated to 0
+        assert_eq!(Err(PriceProviderErr::InvalidPriceErr),    parse_price(payload, b"DOGE", 12));
+        assert_eq!(Err(PriceProviderErr::InvalidPriceErr),    parse_price(payload, b"LUNA", 12));
+        assert_eq!(Err(PriceProviderErr::ParseErr),           parse_price(payload, b"BOGUS", 12));  // overflow
+        assert_eq!(Err(PriceProviderErr::ParseErr),           parse_price(payload, b"DOT", 12));
+        assert_eq!(Err(PriceProviderErr::ParseErr),           parse_price(r#"{"USDT": abc}"#, b"USDT", 12));
+        assert_eq!(Err(PriceProviderErr::ParseErr),           parse_price(r#""USDT": 12"#, b"USDT", 12));
+        assert_eq!(Err(PriceProviderErr::ParseErr),           parse_price(r#"{"USDT": "12a"}"#, b"USDT", 12));
+        assert_eq!(Err(PriceProviderErr::UnsupportedPairErr), parse_price(r#"{"Response": "Error", "Message": "market does not exist for this coin pair"}"#, b"USDT", 12));
+    }
+
+    #[test]
+    fn test_parse_decimal() {
+        assert_eq!(Ok(12_789_000_000_000),  parse_decimal("12.789", 12));
+        assert_eq!(Ok(12_789_000_000_000),  parse_decimal("+12.789", 12));
+        assert_eq!(Ok(12_000_000_000_000),  parse_decimal("12.", 12));
+        assert_eq!(Ok(500_000_000_000),     parse_decimal(".5", 12));
+        assert_eq!(Ok(10_000_000),          parse_decimal("0.00001", 12));
+        assert_eq!(Ok(1),                   parse_decimal("0.000000000001999", 12));  // truncated
+        assert_eq!(Ok(0),                   parse_decimal("0.0000000000001", 12));
+        assert_eq!(Ok(150_000),             parse_decimal("1.5e-7", 12));
+        assert_eq!(Ok(150_000),             parse_decimal("15E-8", 12));
+        assert_eq!(Ok(1_500_000_000_000_000_000), parse_decimal("1.5e+6", 12));
+        assert_eq!(Ok(0),                   parse_decimal("-0.0", 12));
+        assert_eq!(Ok(0),                   parse_decimal("0e999999", 12));
+        assert_eq!(Ok(1),                   parse_decimal("1", 0));
+        assert_eq!(Ok(1),                   parse_decimal(&format!("0.{}1e{}", "0".repeat(100), 89), 12));  // fraction beyond u128 digits
+        assert_eq!(Ok(1_999_999_999_999),   parse_decimal(&format!("1.{}", "9".repeat(100)), 12));
+        assert_eq!(Ok(u128::MAX),           parse_decimal(&u128::MAX.to_string(), 0));
+        assert_eq!(Err(DecimalErr::Overflow), parse_decimal(&u128::MAX.to_string(), 1));
+        assert_eq!(Err(DecimalErr::Overflow), parse_decimal("1e39", 0));
+        assert_eq!(Err(DecimalErr::Negative), parse_decimal("-1.5", 12));
+        assert_eq!(Err(DecimalErr::Malformed), parse_decimal("", 12));
+        assert_eq!(Err(DecimalErr::Malformed), parse_decimal(".", 12));
+        assert_eq!(Err(DecimalErr::Malformed), parse_decimal("1e", 12));
+        assert_eq!(Err(DecimalErr::Malformed), parse_decimal("1.2.3", 12));
+        assert_eq!(Err(DecimalErr::Malformed), parse_decimal("--1", 12));
+        assert_eq!(Err(DecimalErr::Malformed), parse_decimal("0x10", 12));
+    }
+
+    /// Deterministic pseudo random generator (xorshift), for the property tests
+    struct Rng(u64);
+    impl Rng {
+        fn next(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn next_u128(&mut self) -> u128 {
+            (self.next() as u128) << 64 | self.next() as u128
+        }
+    }
+
+    #[test]
+    fn test_parse_decimal_properties() {
+        let mut rng = Rng(0x2545_f491_4f6c_dd1d);
+        for _ in 0..10_000 {
+            let scale = (rng.next() % 39) as u32;
+            let value = rng.next_u128() >> (rng.next() % 128);
+            let divisor = 10_u128.pow(scale);
+            let (integer, fraction) = (value / divisor, value % divisor);
+            let fraction = format!("{:0width$}", fraction, width = scale as usize);
+
+            // plain decimal round trips
+            assert_eq!(Ok(value), parse_decimal(&format!("{}.{}", integer, fraction), scale));
+            // excess digits are truncated
+            assert_eq!(Ok(value), parse_decimal(&format!("{}.{}{}", integer, fraction, rng.next()), scale));
+            // scientific notation, with the mantissa stripped of the decimal point
+            assert_eq!(Ok(value), parse_decimal(&format!("{}e-{}", value, scale), scale));
+            // shifting the exponent is equivalent to shifting the scale
+            let exp = (rng.next() % 20) as u32;
+            assert_eq!(parse_decimal(&format!("{}.{}", integer, fraction), scale + exp), parse_decimal(&format!("{}.{}e{}", integer, fraction, exp), scale));
+            // scaling up either matches exact multiplication or overflows
+            match value.checked_mul(10) {
+                Some(value) => assert_eq!(Ok(value), parse_decimal(&format!("{}.{}", integer, fraction), scale + 1)),
+                None => assert_eq!(Err(DecimalErr::Overflow), parse_decimal(&format!("{}.{}", integer, fraction), scale + 1)),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_decimal_fuzz() {
+        const ALPHABET: &[u8] = b"0123456789.eE+- x";
+        let mut rng = Rng(0x9e37_79b9_7f4a_7c15);
+        for _ in 0..100_000 {
+            let len = (rng.next() % 48) as usize;
+            let input = (0..len).map(|_| ALPHABET[(rng.next() % ALPHABET.len() as u64) as usize] as char).collect::<String>();
+            let scale = (rng.next() % 64) as u32;
+            // must never panic, any success must be consistent with the lower scale
+            if let (Ok(value), Ok(lower_value)) = (parse_decimal(&input, scale + 1), parse_decimal(&input, scale)) {
+                assert_eq!(lower_value, value / 10, "input: {}, scale: {}", input, scale);
+            }
+        }
+    }
+
+    #[test]
+    fn test_mul_scaled() {
+        // 250 * 1.50 = 375, 25 * 0.150 = 3.75
+        assert_eq!(Some(375), mul_scaled(250, 150, 2, Rounding::Down));
+        assert_eq!(Some(3), mul_scaled(25, 150, 3, Rounding::Down));
+        assert_eq!(Some(4), mul_scaled(25, 150, 3, Rounding::Up));
+        assert_eq!(Some(4), mul_scaled(25, 150, 3, Rounding::Nearest));
+        // halves round up when rounding to nearest
+        assert_eq!(Some(2), mul_scaled(25, 100, 3, Rounding::Down));
+        assert_eq!(Some(3), mul_scaled(25, 100, 3, Rounding::Nearest));
+        assert_eq!(Some(2), mul_scaled(249, 10, 3, Rounding::Nearest));
+        // exact results are not rounded
+        assert_eq!(Some(5), mul_scaled(50, 100, 3, Rounding::Up));
+        assert_eq!(Some(0), mul_scaled(0, 100, 3, Rounding::Up));
+        assert_eq!(Some(1), mul_scaled(1, 1, 12, Rounding::Up));
+        assert_eq!(Some(0), mul_scaled(1, 1, 12, Rounding::Nearest));
+        // zero scale
+        assert_eq!(Some(u128::MAX), mul_scaled(u128::MAX, 1, 0, Rounding::Up));
+        // intermediate product exceeding u128 is fine, as long as the result fits
+        assert_eq!(Some(u128::MAX / 1_000), mul_scaled(u128::MAX / 1_000, 10_u128.pow(18), 18, Rounding::Down));
+        assert_eq!(Some(u128::MAX), mul_scaled(u128::MAX, 10_u128.pow(18), 18, Rounding::Down));
+        // overflows
+        assert_eq!(None, mul_scaled(u128::MAX, 10_u128.pow(18) + 1, 18, Rounding::Down));
+        assert_eq!(None, mul_scaled(u128::MAX, 2, 0, Rounding::Down));
+        assert_eq!(None, mul_scaled(u128::MAX, 11, 1, Rounding::Up));
+        assert_eq!(None, mul_scaled(1, 1, 39, Rounding::Down));
+    }
+
+    #[test]
+    fn test_convert_native() {
+        // 1.5 of 18 decimals at 2,000.5 = 3,000.75 of 6 decimals
+        assert_eq!(Some(3_000_750_000), convert_native(1_500_000_000_000_000_000, 2_000_500_000_000_000, 12, 18, 6, Rounding::Down));
+        // and back, at 1 / 2,000.5, rounded down to the 12 decimals of the price
+        assert_eq!(Some(1_499_625_093_000_000_000), convert_native(3_000_000_000, 499_875_031, 12, 6, 18, Rounding::Down));
+        // prices below a single native unit aren't truncated, 1 of 18 decimals at 0.00001 = 10 of 6 decimals
+        assert_eq!(Some(10), convert_native(1_000_000_000_000_000_000, 10_000_000, 12, 18, 6, Rounding::Down));
+        assert_eq!(Some(0), convert_native(1, 10_000_000, 12, 18, 6, Rounding::Down));
+        assert_eq!(Some(1), convert_native(1, 10_000_000, 12, 18, 6, Rounding::Up));
+        // equal decimals are the same as mul_scaled
+        assert_eq!(mul_scaled(25, 150, 3, Rounding::Nearest), convert_native(25, 150, 3, 8, 8, Rounding::Nearest));
+        // overflows
+        assert_eq!(None, convert_native(u128::MAX, 1, 0, 0, 1, Rounding::Down));
+        assert_eq!(None, convert_native(1, 1, 0, 0, 255, Rounding::Down));
+        assert_eq!(None, convert_native(1, 1, 0, 255, 0, Rounding::Down));
+    }
+
+    #[test]
+    fn test_spread_quote() {
+        // 1% spread around 100.00
+        assert_eq!((9_950, 10_050), spread_quote(10_000, 10_000));
+        assert_eq!((10_000, 10_000), spread_quote(10_000, 0));
+        // tiny spreads are rounded outwards on the ask
+        assert_eq!((10, 11), spread_quote(10, 1));
+        assert_eq!((0, 0), spread_quote(0, 10_000));
+        // bid floored at 0, ask saturated
+        assert_eq!((0, 20), spread_quote(10, 2_000_000));
+        assert_eq!((u128::MAX - u128::MAX / 200, u128::MAX), spread_quote(u128::MAX, 10_000));
+    }
+
+    #[test]
+    fn test_rescale() {
+        assert_eq!(Some(99), rescale(100, 101, 100));
+        assert_eq!(Some(200), rescale(100, 1, 2));
+        assert_eq!(Some(u128::MAX), rescale(u128::MAX, 3, 3));
+        assert_eq!(None, rescale(u128::MAX, 1, 2));
+        assert_eq!(None, rescale(1, 0, 2));
+    }
+
+    #[test]
+    fn test_decay_confidence() {
+        assert_eq!(1_000_000, decay_confidence(1_000_000, 0, 10));
+        assert_eq!(700_000, decay_confidence(1_000_000, 3, 10));
+        assert_eq!(0, decay_confidence(1_000_000, 10, 10));
+        assert_eq!(0, decay_confidence(1_000_000, u128::MAX, 10));
+        assert_eq!(1_000_000, decay_confidence(1_000_000, u128::MAX, 0));
+        assert_eq!(0, decay_confidence(0, 0, 10));
+    }
+
+    #[test]
+    fn test_inverse_price() {
+        // 1 / 2.00 = 0.50, 1 / 3.000 = 0.333
+        assert_eq!(Some(50), inverse_price(200, 2, 0));
+        assert_eq!(Some(333), inverse_price(3_000, 3, 0));
+        // 1% haircut
+        assert_eq!(Some(495_000), inverse_price(2_000_000, 6, 10_000));
+        assert_eq!(Some(0), inverse_price(200, 2, 1_000_000));
+        // beyond u128 intermediate products
+        assert_eq!(Some(10_u128.pow(36)), inverse_price(1, 18, 0));
+        // failures
+        assert_eq!(None, inverse_price(0, 2, 0));
+        assert_eq!(None, inverse_price(200, 2, 1_000_001));
+        assert_eq!(None, inverse_price(1, 20, 0));
+        assert_eq!(None, inverse_price(1, 39, 0));
     }
 
     #[test]
@@ -72,5 +445,7 @@ mod tests {
         assert!(! breaches_tolerance(1_000_005, 1_000_001, 4));
         assert!(breaches_tolerance(1_002, 1_000, 1_000));
         assert!(breaches_tolerance(1_002, 1_008, 5_000));
+        assert!(! breaches_tolerance(0, 0, 0));
+        assert!(breaches_tolerance(0, 1, 1_000_000));
     }
 }
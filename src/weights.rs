@@ -1,21 +1,7 @@
-//! Autogenerated weights for pallet_best_path
+//! Estimated weights for pallet_best_path
 //!
-//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 4.0.0-dev
-//! DATE: 2022-02-08, STEPS: `1`, REPEAT: 1, LOW RANGE: `[]`, HIGH RANGE: `[]`
-//! EXECUTION: None, WASM-EXECUTION: Interpreted, CHAIN: None, DB CACHE: 128
-
-// Executed Command:
-// target/debug/node-template
-// benchmark
-// --extrinsic
-// *
-// --pallet
-// pallet_best_path
-// --wasm-execution
-// interpreted-i-know-what-i-do
-// --output
-// ./pallets/best_path/src/weights.rs
-// --template=frame-weight-template.hbs
+//! These are not the output of the benchmark CLI. Storage accesses are as per the benchmarks in `benchmarking.rs`,
+//! execution times are estimates. Replace them with the output of `make benchmark` on the reference hardware.
 
 #![allow(unused_parens)]
 #![allow(unused_imports)]
@@ -26,56 +12,226 @@ use sp_std::marker::PhantomData;
 
 /// Weight functions needed for pallet_best_path.
 pub trait WeightInfo {
-	fn add_price_pair_nonexisting() -> Weight;
-	fn add_price_pair_existing() -> Weight;
-	fn remove_price_pair() -> Weight;
-	fn submit_monitored_pairs(_i: usize, ) -> Weight;
-	fn ocw_submit_best_paths_changes(_i: usize, ) -> Weight;
+	fn submit_monitored_pairs(i: u32, ) -> Weight;
+	fn ocw_submit_best_paths_changes(c: u32, s: u32, ) -> Weight;
 	fn add_whitelisted_offchain_authority() -> Weight;
+	fn submit_currency_metadata() -> Weight;
+	fn submit_provider_tickers(i: u32, ) -> Weight;
+	fn submit_pair_tolerances(i: u32, ) -> Weight;
+	fn submit_currency_groups(i: u32, ) -> Weight;
+	fn submit_group_tolerances(i: u32, ) -> Weight;
+	fn approve_suspicious_change() -> Weight;
+	fn reject_suspicious_change() -> Weight;
+	fn pause() -> Weight;
+	fn unpause() -> Weight;
+	fn execute_best_path(h: u32, ) -> Weight;
 }
 
-/// Weights for pallet_best_path using the Substrate node and recommended hardware.
+/// Estimated weights for pallet_best_path, to be replaced by those benchmarked on the recommended hardware.
 pub struct SubstrateWeight<T>(PhantomData<T>);
 impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
-	fn add_price_pair_nonexisting() -> Weight {
-		(155_000_000 as Weight)			.saturating_add(T::DbWeight::get().reads(1 as Weight))			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	// Storage: BestPath MonitoredPairs (r:1 w:1)
+	fn submit_monitored_pairs(i: u32, ) -> Weight {
+		(9_512_000 as Weight)
+			.saturating_add((4_381_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(i as Weight)))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
 	}
-	fn add_price_pair_existing() -> Weight {
-		(51_000_000 as Weight)			.saturating_add(T::DbWeight::get().reads(1 as Weight))			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	// Storage: BestPath Paused (r:1 w:0)
+	// Storage: BestPath WhitelistedOffchainAuthorities (r:1 w:0)
+	// Storage: BestPath UnsignedTxNonce (r:1 w:1)
+	// Storage: BestPath PausedProviders (r:1 w:0)
+	// Storage: BestPath BestPaths (r:1 w:1)
+	// Storage: BestPath PriceWindowAnchors (r:1 w:1)
+	// Storage: BestPath PendingSuspiciousChanges (r:0 w:1)
+	// Storage: BestPath SuspiciousChangeExpiry (r:1 w:1)
+	// Storage: BestPath PathHistory (r:1 w:1)
+	// Storage: BestPath PathHistoryExpiry (r:1 w:1)
+	// Storage: BestPath TwapObservations (r:1 w:1)
+	fn ocw_submit_best_paths_changes(c: u32, s: u32, ) -> Weight {
+		(31_604_000 as Weight)
+			.saturating_add((38_717_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((1_906_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().reads((7 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((7 as Weight).saturating_mul(c as Weight)))
 	}
-	fn remove_price_pair() -> Weight {
-		(156_000_000 as Weight)			.saturating_add(T::DbWeight::get().reads(1 as Weight))			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	// Storage: BestPath WhitelistedOffchainAuthorities (r:0 w:1)
+	fn add_whitelisted_offchain_authority() -> Weight {
+		(14_870_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn submit_monitored_pairs(_i: usize, ) -> Weight {
-		(20_827_000_000 as Weight)			.saturating_add(T::DbWeight::get().reads(250 as Weight))			.saturating_add(T::DbWeight::get().writes(250 as Weight))
+	// Storage: BestPath CurrencyMetadata (r:0 w:1)
+	fn submit_currency_metadata() -> Weight {
+		(15_330_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
 	}
-	fn ocw_submit_best_paths_changes(_i: usize, ) -> Weight {
-		(143_000_000 as Weight)			.saturating_add(T::DbWeight::get().reads(1 as Weight))			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	// Storage: BestPath ProviderTickers (r:1 w:1)
+	// Storage: BestPath ProviderTickerCurrencies (r:1 w:2)
+	fn submit_provider_tickers(i: u32, ) -> Weight {
+		(12_208_000 as Weight)
+			.saturating_add((11_564_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+			.saturating_add(T::DbWeight::get().writes((4 as Weight).saturating_mul(i as Weight)))
 	}
-	fn add_whitelisted_offchain_authority() -> Weight {
-		(138_000_000 as Weight)			.saturating_add(T::DbWeight::get().reads(1 as Weight))			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	// Storage: BestPath PairTolerances (r:0 w:1)
+	fn submit_pair_tolerances(i: u32, ) -> Weight {
+		(10_240_000 as Weight)
+			.saturating_add((2_310_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+	}
+	// Storage: BestPath CurrencyGroups (r:0 w:1)
+	fn submit_currency_groups(i: u32, ) -> Weight {
+		(10_105_000 as Weight)
+			.saturating_add((2_420_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+	}
+	// Storage: BestPath GroupTolerances (r:0 w:1)
+	fn submit_group_tolerances(i: u32, ) -> Weight {
+		(9_980_000 as Weight)
+			.saturating_add((2_390_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(T::DbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+	}
+	// Storage: BestPath PendingSuspiciousChanges (r:1 w:1)
+	// Storage: BestPath BestPaths (r:1 w:1)
+	// Storage: BestPath PathHistory (r:1 w:1)
+	// Storage: BestPath PathHistoryExpiry (r:1 w:1)
+	// Storage: BestPath TwapObservations (r:1 w:1)
+	// Storage: BestPath PriceWindowAnchors (r:0 w:1)
+	fn approve_suspicious_change() -> Weight {
+		(52_940_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(5 as Weight))
+			.saturating_add(T::DbWeight::get().writes(6 as Weight))
+	}
+	// Storage: BestPath PendingSuspiciousChanges (r:1 w:1)
+	fn reject_suspicious_change() -> Weight {
+		(19_450_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: BestPath PausedProviders (r:0 w:1)
+	fn pause() -> Weight {
+		(13_020_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: BestPath PausedProviders (r:0 w:1)
+	fn unpause() -> Weight {
+		(13_470_000 as Weight)
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	// Storage: BestPath Paused (r:1 w:0)
+	// Storage: BestPath BestPaths (r:1 w:0)
+	// Storage: BestPath PausedProviders (r:1 w:0)
+	fn execute_best_path(h: u32, ) -> Weight {
+		(27_786_000 as Weight)
+			.saturating_add((14_512_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(T::DbWeight::get().reads(2 as Weight))
+			.saturating_add(T::DbWeight::get().reads((1 as Weight).saturating_mul(h as Weight)))
 	}
 }
 
 // For backwards compatibility and tests
 impl WeightInfo for () {
-	fn add_price_pair_nonexisting() -> Weight {
-		(155_000_000 as Weight)			.saturating_add(RocksDbWeight::get().reads(1 as Weight))			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	// Storage: BestPath MonitoredPairs (r:1 w:1)
+	fn submit_monitored_pairs(i: u32, ) -> Weight {
+		(9_512_000 as Weight)
+			.saturating_add((4_381_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(i as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
 	}
-	fn add_price_pair_existing() -> Weight {
-		(51_000_000 as Weight)			.saturating_add(RocksDbWeight::get().reads(1 as Weight))			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	// Storage: BestPath Paused (r:1 w:0)
+	// Storage: BestPath WhitelistedOffchainAuthorities (r:1 w:0)
+	// Storage: BestPath UnsignedTxNonce (r:1 w:1)
+	// Storage: BestPath PausedProviders (r:1 w:0)
+	// Storage: BestPath BestPaths (r:1 w:1)
+	// Storage: BestPath PriceWindowAnchors (r:1 w:1)
+	// Storage: BestPath PendingSuspiciousChanges (r:0 w:1)
+	// Storage: BestPath SuspiciousChangeExpiry (r:1 w:1)
+	// Storage: BestPath PathHistory (r:1 w:1)
+	// Storage: BestPath PathHistoryExpiry (r:1 w:1)
+	// Storage: BestPath TwapObservations (r:1 w:1)
+	fn ocw_submit_best_paths_changes(c: u32, s: u32, ) -> Weight {
+		(31_604_000 as Weight)
+			.saturating_add((38_717_000 as Weight).saturating_mul(c as Weight))
+			.saturating_add((1_906_000 as Weight).saturating_mul(s as Weight))
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((7 as Weight).saturating_mul(c as Weight)))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(s as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((7 as Weight).saturating_mul(c as Weight)))
 	}
-	fn remove_price_pair() -> Weight {
-		(156_000_000 as Weight)			.saturating_add(RocksDbWeight::get().reads(1 as Weight))			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	// Storage: BestPath WhitelistedOffchainAuthorities (r:0 w:1)
+	fn add_whitelisted_offchain_authority() -> Weight {
+		(14_870_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn submit_monitored_pairs(_i: usize, ) -> Weight {
-		(20_827_000_000 as Weight)			.saturating_add(RocksDbWeight::get().reads(250 as Weight))			.saturating_add(RocksDbWeight::get().writes(250 as Weight))
+	// Storage: BestPath CurrencyMetadata (r:0 w:1)
+	fn submit_currency_metadata() -> Weight {
+		(15_330_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
 	}
-	fn ocw_submit_best_paths_changes(_i: usize, ) -> Weight {
-		(143_000_000 as Weight)			.saturating_add(RocksDbWeight::get().reads(1 as Weight))			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	// Storage: BestPath ProviderTickers (r:1 w:1)
+	// Storage: BestPath ProviderTickerCurrencies (r:1 w:2)
+	fn submit_provider_tickers(i: u32, ) -> Weight {
+		(12_208_000 as Weight)
+			.saturating_add((11_564_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(i as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((4 as Weight).saturating_mul(i as Weight)))
 	}
-	fn add_whitelisted_offchain_authority() -> Weight {
-		(138_000_000 as Weight)			.saturating_add(RocksDbWeight::get().reads(1 as Weight))			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	// Storage: BestPath PairTolerances (r:0 w:1)
+	fn submit_pair_tolerances(i: u32, ) -> Weight {
+		(10_240_000 as Weight)
+			.saturating_add((2_310_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+	}
+	// Storage: BestPath CurrencyGroups (r:0 w:1)
+	fn submit_currency_groups(i: u32, ) -> Weight {
+		(10_105_000 as Weight)
+			.saturating_add((2_420_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+	}
+	// Storage: BestPath GroupTolerances (r:0 w:1)
+	fn submit_group_tolerances(i: u32, ) -> Weight {
+		(9_980_000 as Weight)
+			.saturating_add((2_390_000 as Weight).saturating_mul(i as Weight))
+			.saturating_add(RocksDbWeight::get().writes((1 as Weight).saturating_mul(i as Weight)))
+	}
+	// Storage: BestPath PendingSuspiciousChanges (r:1 w:1)
+	// Storage: BestPath BestPaths (r:1 w:1)
+	// Storage: BestPath PathHistory (r:1 w:1)
+	// Storage: BestPath PathHistoryExpiry (r:1 w:1)
+	// Storage: BestPath TwapObservations (r:1 w:1)
+	// Storage: BestPath PriceWindowAnchors (r:0 w:1)
+	fn approve_suspicious_change() -> Weight {
+		(52_940_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(5 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(6 as Weight))
+	}
+	// Storage: BestPath PendingSuspiciousChanges (r:1 w:1)
+	fn reject_suspicious_change() -> Weight {
+		(19_450_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: BestPath PausedProviders (r:0 w:1)
+	fn pause() -> Weight {
+		(13_020_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: BestPath PausedProviders (r:0 w:1)
+	fn unpause() -> Weight {
+		(13_470_000 as Weight)
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	// Storage: BestPath Paused (r:1 w:0)
+	// Storage: BestPath BestPaths (r:1 w:0)
+	// Storage: BestPath PausedProviders (r:1 w:0)
+	fn execute_best_path(h: u32, ) -> Weight {
+		(27_786_000 as Weight)
+			.saturating_add((14_512_000 as Weight).saturating_mul(h as Weight))
+			.saturating_add(RocksDbWeight::get().reads(2 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((1 as Weight).saturating_mul(h as Weight)))
 	}
 }
-